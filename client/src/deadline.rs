@@ -0,0 +1,176 @@
+use std::marker::PhantomData;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{Layer, LayerError, ServiceClient};
+
+/// Fails a request with a timeout once it runs longer than `timeout`.
+/// The wrapped client runs on a worker thread, so a request past its deadline still completes in the background
+/// and the next request waits for it.
+pub struct Deadline<Request, Response, Error> {
+    timeout: Duration,
+    requests: Sender<(u64, Request)>,
+    responses: Receiver<(u64, Result<Response, Error>)>,
+    next_request_seq: u64,
+}
+
+impl<Request, Response, Error> Deadline<Request, Response, Error>
+where
+    Request: Send + 'static,
+    Response: Send + 'static,
+    Error: Send + 'static,
+{
+    pub fn new<Client>(mut inner: Client, timeout: Duration) -> Self
+    where
+        Client: ServiceClient<Request, Response, Error> + Send + 'static,
+    {
+        let (requests, pending_requests) = mpsc::channel::<(u64, Request)>();
+        let (completed_requests, responses) = mpsc::channel();
+        // Ends once the client side is dropped or stops listening
+        thread::spawn(move || {
+            for (request_seq, request) in pending_requests {
+                if completed_requests.send((request_seq, inner.run_request(&request))).is_err() {
+                    return;
+                }
+            }
+        });
+        Self {
+            timeout,
+            requests,
+            responses,
+            next_request_seq: 0,
+        }
+    }
+}
+
+impl<Request, Response, Error> ServiceClient<Request, Response, Error> for Deadline<Request, Response, Error>
+where
+    Request: Clone,
+    Error: LayerError,
+{
+    fn run_request(&mut self, request: &Request) -> Result<Response, Error> {
+        let deadline = Instant::now() + self.timeout;
+        let request_seq = self.next_request_seq;
+        self.next_request_seq += 1;
+        if self.requests.send((request_seq, request.clone())).is_err() {
+            return Err(Error::unavailable("the deadline worker is gone".into()));
+        }
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.responses.recv_timeout(remaining) {
+                Ok((seq, result)) if seq == request_seq => return result,
+                // Completion of an earlier request which ran out of time
+                Ok(_) => continue,
+                Err(RecvTimeoutError::Timeout) => return Err(Error::timeout()),
+                Err(RecvTimeoutError::Disconnected) => return Err(Error::unavailable("the deadline worker is gone".into())),
+            }
+        }
+    }
+}
+
+/// Wraps clients into a `Deadline`.
+/// A request past its deadline is not cancelled: it still reaches the service and any request run afterwards,
+/// a retry of it included, is queued behind it. Do not retry deadline timeouts of requests which must not run twice.
+pub struct DeadlineLayer<Request, Response, Error> {
+    timeout: Duration,
+    _client: PhantomData<fn(Request) -> Result<Response, Error>>,
+}
+
+impl<Request, Response, Error> DeadlineLayer<Request, Response, Error> {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            _client: PhantomData,
+        }
+    }
+}
+
+impl<Client, Request, Response, Error> Layer<Client> for DeadlineLayer<Request, Response, Error>
+where
+    Client: ServiceClient<Request, Response, Error> + Send + 'static,
+    Request: Send + 'static,
+    Response: Send + 'static,
+    Error: Send + 'static,
+{
+    type Client = Deadline<Request, Response, Error>;
+
+    fn layer(&self, inner: Client) -> Self::Client {
+        Deadline::new(inner, self.timeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{ClientError, RetryLayer, RetryPolicy};
+
+    #[test]
+    fn sanity() {
+        // GIVEN
+        let layer = DeadlineLayer::new(Duration::from_millis(50));
+
+        // WHEN
+        let mut client: Deadline<Duration, Duration, ClientError> = layer.layer(SleepingClient);
+
+        // THEN
+        assert_eq!(Duration::from_millis(1), client.run_request(&Duration::from_millis(1)).unwrap());
+        assert!(client.run_request(&Duration::from_millis(70)).unwrap_err().is_timeout());
+        // Queued behind the late request, whose completion is thrown away
+        assert_eq!(Duration::from_millis(2), client.run_request(&Duration::from_millis(2)).unwrap());
+    }
+
+    #[test]
+    fn retry_over_deadline() {
+        // GIVEN
+        let test_policy = RetryPolicy {
+            max_attempts: 2,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+        };
+        let runs = Arc::new(AtomicUsize::new(0));
+        let deadline_layer = DeadlineLayer::new(Duration::from_millis(20));
+
+        // WHEN
+        let mut single_client = RetryLayer::new(test_policy.clone(), |_: &ClientError| false)
+            .layer(deadline_layer.layer(CountingSleepingClient { runs: runs.clone() }));
+        let single_result = single_client.run_request(&Duration::from_millis(40));
+        thread::sleep(Duration::from_millis(60));
+        let single_runs = runs.swap(0, Ordering::SeqCst);
+        let mut retrying_client = RetryLayer::new(test_policy, ClientError::is_timeout)
+            .layer(deadline_layer.layer(CountingSleepingClient { runs: runs.clone() }));
+        let retrying_result = retrying_client.run_request(&Duration::from_millis(40));
+        thread::sleep(Duration::from_millis(100));
+
+        // THEN
+        assert!(single_result.unwrap_err().is_timeout());
+        assert_eq!(1, single_runs);
+        // The timed out attempt was not cancelled, so the service got the request twice
+        assert!(retrying_result.unwrap_err().is_timeout());
+        assert_eq!(2, runs.load(Ordering::SeqCst));
+    }
+
+    struct CountingSleepingClient {
+        runs: Arc<AtomicUsize>,
+    }
+
+    impl ServiceClient<Duration, Duration, ClientError> for CountingSleepingClient {
+        fn run_request(&mut self, request: &Duration) -> Result<Duration, ClientError> {
+            self.runs.fetch_add(1, Ordering::SeqCst);
+            thread::sleep(*request);
+            Ok(*request)
+        }
+    }
+
+    struct SleepingClient;
+
+    impl ServiceClient<Duration, Duration, ClientError> for SleepingClient {
+        fn run_request(&mut self, request: &Duration) -> Result<Duration, ClientError> {
+            thread::sleep(*request);
+            Ok(*request)
+        }
+    }
+}
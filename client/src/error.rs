@@ -0,0 +1,31 @@
+use std::error::Error;
+
+use ipc::IpcError;
+
+/// Failure of a single service request, the remote side never answered it with a valid response.
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("transport failure")]
+    Ipc(#[from] IpcError),
+    #[error("failed to encode request")]
+    Encode(#[source] Box<dyn Error + Send + Sync>),
+    #[error("failed to decode response")]
+    Decode(#[source] Box<dyn Error + Send + Sync>),
+    #[error("received response ID {response_id} is ahead of request ID {request_id}")]
+    UnexpectedResponse { request_id: u32, response_id: u32 },
+}
+
+impl ClientError {
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, Self::Ipc(IpcError::Timeout))
+    }
+
+    pub fn is_reconnect(&self) -> bool {
+        matches!(self, Self::Ipc(IpcError::Reconnected))
+    }
+}
+
+/// A layer was set up with parameters it cannot work with.
+#[derive(Debug, thiserror::Error)]
+#[error("invalid layer parameter: {0}")]
+pub struct InvalidLayerParameter(pub String);
@@ -0,0 +1,50 @@
+use ipc::IpcError;
+
+use crate::ClientError;
+
+/// Wraps a service client into another one adding some behaviour, the same way tower layers do.
+pub trait Layer<Client> {
+    type Client;
+
+    fn layer(&self, inner: Client) -> Self::Client;
+}
+
+/// Applies `inner` first, then `outer` on top of it.
+pub struct Stack<Inner, Outer> {
+    inner: Inner,
+    outer: Outer,
+}
+
+impl<Inner, Outer> Stack<Inner, Outer> {
+    pub fn new(inner: Inner, outer: Outer) -> Self {
+        Self { inner, outer }
+    }
+}
+
+impl<Client, Inner, Outer> Layer<Client> for Stack<Inner, Outer>
+where
+    Inner: Layer<Client>,
+    Outer: Layer<Inner::Client>,
+{
+    type Client = Outer::Client;
+
+    fn layer(&self, inner: Client) -> Self::Client {
+        self.outer.layer(self.inner.layer(inner))
+    }
+}
+
+/// Errors the layers report on their own, without the wrapped client failing.
+pub trait LayerError {
+    fn timeout() -> Self;
+    fn unavailable(reason: String) -> Self;
+}
+
+impl LayerError for ClientError {
+    fn timeout() -> Self {
+        Self::Ipc(IpcError::Timeout)
+    }
+
+    fn unavailable(reason: String) -> Self {
+        Self::Ipc(IpcError::Unavailable(reason))
+    }
+}
@@ -1,3 +1,30 @@
+pub use deadline::{Deadline, DeadlineLayer};
+pub use error::{ClientError, InvalidLayerParameter};
+pub use layers::{Layer, LayerError, Stack};
+pub use rate_limit::{RateLimit, RateLimitLayer};
+pub use retry::{Retry, RetryLayer, RetryPolicy};
+
 pub trait ServiceClient<Request, Response, Error> {
     fn run_request(&mut self, request: &Request) -> Result<Response, Error>;
-}
\ No newline at end of file
+}
+
+impl<Request, Response, Error, Client> ServiceClient<Request, Response, Error> for Box<Client>
+where
+    Client: ServiceClient<Request, Response, Error> + ?Sized,
+{
+    fn run_request(&mut self, request: &Request) -> Result<Response, Error> {
+        (**self).run_request(request)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+pub trait AsyncServiceClient<Request: Sync, Response, Error> {
+    async fn run_request(&mut self, request: &Request) -> Result<Response, Error>;
+}
+
+mod deadline;
+mod error;
+mod layers;
+mod rate_limit;
+mod retry;
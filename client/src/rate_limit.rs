@@ -0,0 +1,157 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{InvalidLayerParameter, Layer, ServiceClient};
+
+/// Lets at most `max_requests` requests through per `period`, later ones wait for their turn.
+/// Bursts up to `max_requests` go through right away.
+pub struct RateLimit<Client> {
+    inner: Client,
+    max_requests: u32,
+    period: Duration,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl<Client> RateLimit<Client> {
+    pub fn new(inner: Client, max_requests: u32, period: Duration) -> Result<Self, InvalidLayerParameter> {
+        validate(max_requests, period)?;
+        Ok(Self {
+            inner,
+            max_requests,
+            period,
+            tokens: max_requests as f64,
+            refilled_at: Instant::now(),
+        })
+    }
+
+    fn wait_for_token(&mut self) {
+        loop {
+            let now = Instant::now();
+            let refill = now.duration_since(self.refilled_at).as_secs_f64() / self.period.as_secs_f64() * self.max_requests as f64;
+            self.tokens = (self.tokens + refill).min(self.max_requests as f64);
+            self.refilled_at = now;
+            if self.tokens >= 1.0 {
+                self.tokens -= 1.0;
+                return;
+            }
+            thread::sleep(self.period.mul_f64((1.0 - self.tokens) / self.max_requests as f64));
+        }
+    }
+}
+
+impl<Request, Response, Error, Client> ServiceClient<Request, Response, Error> for RateLimit<Client>
+where
+    Client: ServiceClient<Request, Response, Error>,
+{
+    fn run_request(&mut self, request: &Request) -> Result<Response, Error> {
+        self.wait_for_token();
+        self.inner.run_request(request)
+    }
+}
+
+pub struct RateLimitLayer {
+    max_requests: u32,
+    period: Duration,
+}
+
+impl RateLimitLayer {
+    pub fn new(max_requests: u32, period: Duration) -> Result<Self, InvalidLayerParameter> {
+        validate(max_requests, period)?;
+        Ok(Self { max_requests, period })
+    }
+}
+
+impl<Client> Layer<Client> for RateLimitLayer {
+    type Client = RateLimit<Client>;
+
+    fn layer(&self, inner: Client) -> Self::Client {
+        RateLimit {
+            inner,
+            max_requests: self.max_requests,
+            period: self.period,
+            tokens: self.max_requests as f64,
+            refilled_at: Instant::now(),
+        }
+    }
+}
+
+// The token refill rate is max_requests per period, neither may be zero
+fn validate(max_requests: u32, period: Duration) -> Result<(), InvalidLayerParameter> {
+    if max_requests == 0 {
+        return Err(InvalidLayerParameter("the rate limit must let at least one request through".into()));
+    }
+    if period.is_zero() {
+        return Err(InvalidLayerParameter("the rate limit period must not be zero".into()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClientError, RetryLayer, RetryPolicy, Stack};
+
+    #[test]
+    fn sanity() {
+        // GIVEN
+        let layer = RateLimitLayer::new(2, Duration::from_millis(100)).unwrap();
+
+        // WHEN
+        let mut client = layer.layer(CountingClient::default());
+        let start = Instant::now();
+
+        // THEN
+        client.run_request(&()).unwrap();
+        client.run_request(&()).unwrap();
+        assert!(start.elapsed() < Duration::from_millis(40));
+        client.run_request(&()).unwrap();
+        client.run_request(&()).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(95));
+        assert_eq!(4, client.inner.requests);
+    }
+
+    #[test]
+    fn stacked_layers() {
+        // GIVEN
+        let test_policy = RetryPolicy {
+            max_attempts: 2,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+        };
+        let layers = Stack::new(RateLimitLayer::new(1, Duration::from_millis(50)).unwrap(), RetryLayer::new(test_policy, |_: &ClientError| true));
+
+        // WHEN
+        let mut client = layers.layer(CountingClient { failures: 1, ..Default::default() });
+        let start = Instant::now();
+
+        // THEN
+        client.run_request(&()).unwrap();
+        // Retries go through the rate limit too
+        assert!(start.elapsed() >= Duration::from_millis(45));
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        // THEN
+        assert!(RateLimitLayer::new(0, Duration::from_millis(100)).is_err());
+        assert!(RateLimitLayer::new(1, Duration::ZERO).is_err());
+        assert!(RateLimit::new(CountingClient::default(), 0, Duration::from_secs(1)).is_err());
+    }
+
+    #[derive(Default)]
+    struct CountingClient {
+        requests: usize,
+        failures: usize,
+    }
+
+    impl ServiceClient<(), (), ClientError> for CountingClient {
+        fn run_request(&mut self, _request: &()) -> Result<(), ClientError> {
+            self.requests += 1;
+            match self.requests <= self.failures {
+                true => Err(ClientError::Ipc(ipc::IpcError::Timeout)),
+                false => Ok(()),
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::{Layer, ServiceClient};
+
+/// Attempts are spaced by a backoff doubling from `initial_backoff` up to `max_backoff`.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    pub max_attempts: usize,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+/// Runs a failed request again while `is_retryable` accepts its error and attempts are left.
+/// Only retry errors after which running the request twice is harmless, e.g. a lost response may follow an executed movement.
+pub struct Retry<Client, Error> {
+    inner: Client,
+    policy: RetryPolicy,
+    is_retryable: Arc<dyn Fn(&Error) -> bool + Send + Sync>,
+}
+
+impl<Client, Error> Retry<Client, Error> {
+    pub fn new(inner: Client, policy: RetryPolicy, is_retryable: Arc<dyn Fn(&Error) -> bool + Send + Sync>) -> Self {
+        Self {
+            inner,
+            policy,
+            is_retryable,
+        }
+    }
+}
+
+impl<Request, Response, Error, Client> ServiceClient<Request, Response, Error> for Retry<Client, Error>
+where
+    Client: ServiceClient<Request, Response, Error>,
+{
+    fn run_request(&mut self, request: &Request) -> Result<Response, Error> {
+        let mut backoff = self.policy.initial_backoff;
+        let mut attempts = 1;
+        loop {
+            match self.inner.run_request(request) {
+                Err(err) if attempts < self.policy.max_attempts && (self.is_retryable)(&err) => {
+                    thread::sleep(backoff);
+                    backoff = (backoff * 2).min(self.policy.max_backoff);
+                    attempts += 1;
+                },
+                result => return result,
+            }
+        }
+    }
+}
+
+pub struct RetryLayer<Error> {
+    policy: RetryPolicy,
+    is_retryable: Arc<dyn Fn(&Error) -> bool + Send + Sync>,
+}
+
+impl<Error> RetryLayer<Error> {
+    pub fn new(policy: RetryPolicy, is_retryable: impl Fn(&Error) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            policy,
+            is_retryable: Arc::new(is_retryable),
+        }
+    }
+}
+
+impl<Client, Error> Layer<Client> for RetryLayer<Error> {
+    type Client = Retry<Client, Error>;
+
+    fn layer(&self, inner: Client) -> Self::Client {
+        Retry::new(inner, self.policy.clone(), self.is_retryable.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ipc::IpcError;
+
+    use super::*;
+    use crate::ClientError;
+
+    #[test]
+    fn sanity() {
+        // GIVEN
+        let test_policy = RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(2),
+        };
+        let layer = RetryLayer::new(test_policy, ClientError::is_timeout);
+
+        // WHEN
+        let mut recovering_client = layer.layer(ScriptedClient::new(vec![timeout(), timeout(), Ok(7)]));
+        let mut failing_client = layer.layer(ScriptedClient::new(vec![timeout(), timeout(), timeout(), Ok(7)]));
+        let mut rejecting_client = layer.layer(ScriptedClient::new(vec![Err(ClientError::Ipc(IpcError::Reconnected)), Ok(7)]));
+
+        // THEN
+        assert_eq!(7, recovering_client.run_request(&()).unwrap());
+        assert!(failing_client.run_request(&()).unwrap_err().is_timeout());
+        assert_eq!(3, failing_client.inner.attempts);
+        assert!(rejecting_client.run_request(&()).unwrap_err().is_reconnect());
+        assert_eq!(1, rejecting_client.inner.attempts);
+    }
+
+    fn timeout() -> Result<u32, ClientError> {
+        Err(ClientError::Ipc(IpcError::Timeout))
+    }
+
+    struct ScriptedClient {
+        results: Vec<Result<u32, ClientError>>,
+        attempts: usize,
+    }
+
+    impl ScriptedClient {
+        fn new(mut results: Vec<Result<u32, ClientError>>) -> Self {
+            results.reverse();
+            Self { results, attempts: 0 }
+        }
+    }
+
+    impl ServiceClient<(), u32, ClientError> for ScriptedClient {
+        fn run_request(&mut self, _request: &()) -> Result<u32, ClientError> {
+            self.attempts += 1;
+            self.results.pop().expect("no more scripted results")
+        }
+    }
+}
@@ -1,4 +1,7 @@
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tonic_build::compile_protos("resources/movement_vendor_api.proto")?;
+    tonic_build::configure()
+        // The oneof variants are named after the request messages
+        .type_attribute("movement_vendor_api.MovementApiRequest.request", "#[allow(clippy::enum_variant_names)]")
+        .compile_protos(&["resources/movement_vendor_api.proto"], &["resources"])?;
     Ok(())
-}
\ No newline at end of file
+}
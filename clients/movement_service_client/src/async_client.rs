@@ -0,0 +1,108 @@
+use ipc::{AsyncIpcReader, AsyncIpcWriter, IpcError};
+use client::{AsyncServiceClient, ClientError};
+use movement_data::{Correlated, MovementApiRequest, MovementApiResponse};
+
+use crate::{correlate_response, CorrelatedCodec, DataTransformer, TransformError};
+
+pub type AsyncRequestSerializer = dyn DataTransformer<Correlated<MovementApiRequest>, Vec<u8>, TransformError> + Send + Sync;
+pub type AsyncResponseParser = dyn DataTransformer<Vec<u8>, Correlated<MovementApiResponse>, TransformError> + Send + Sync;
+pub type AsyncRawDataReader = dyn AsyncIpcReader<Vec<u8>, IpcError> + Send;
+pub type AsyncRawDataWriter = dyn AsyncIpcWriter<Vec<u8>, IpcError> + Send;
+
+pub struct AsyncMovementServiceClient {
+    raw_data_reader:        Box<AsyncRawDataReader>,
+    raw_data_writer:        Box<AsyncRawDataWriter>,
+    codec:                  CorrelatedCodec<AsyncRequestSerializer, AsyncResponseParser>,
+}
+
+impl AsyncMovementServiceClient {
+    pub fn new(
+        raw_data_reader:        Box<AsyncRawDataReader>,
+        raw_data_writer:        Box<AsyncRawDataWriter>,
+        request_serializer:     Box<AsyncRequestSerializer>,
+        response_parser:        Box<AsyncResponseParser>,
+    ) -> Self {
+        Self {
+            raw_data_reader,
+            raw_data_writer,
+            codec: CorrelatedCodec::new(request_serializer, response_parser),
+        }
+    }
+
+    /// Total number of late responses to earlier requests thrown away while waiting for a matching one.
+    pub fn discarded_responses(&self) -> usize {
+        self.codec.discarded_responses()
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncServiceClient<MovementApiRequest, MovementApiResponse, ClientError> for AsyncMovementServiceClient {
+    async fn run_request(&mut self, request: &MovementApiRequest) -> Result<MovementApiResponse, ClientError> {
+        let (request_id, serial_request) = self.codec.encode_request(request)?;
+        self.raw_data_writer.write_data(&serial_request).await?;
+        loop {
+            let serial_response = self.raw_data_reader.read_data().await?;
+            let response = self.codec.decode_response(&serial_response)?;
+            match correlate_response(request_id, response)? {
+                Some(response) => return Ok(response),
+                None => self.codec.discard_response(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use mockall::mock;
+    use movement_data::{StatusCode, Vector};
+    use serde_json::json;
+
+    use crate::{JsonRequestSerializer, JsonResponseParser};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn client_run_request_sanity() {
+        // GIVEN
+        let test_request = MovementApiRequest::LinearMovement {
+            destination: Vector::new(1.0, 2.0, 3.0),
+            speed: 4.0,
+        };
+        let mut test_raw_data_reader = MockAsyncIpcReader::default();
+        test_raw_data_reader
+            .expect_read_data()
+            .returning(|| Ok(serde_json::to_vec(&json!({ "request_id": 1, "status": "SUCCESS" })).unwrap()));
+        let mut test_raw_data_writer = MockAsyncIpcWriter::default();
+        test_raw_data_writer
+            .expect_write_data()
+            .times(1)
+            .returning(|_| Ok(()));
+
+        // WHEN
+        let mut client = AsyncMovementServiceClient::new(
+            Box::new(test_raw_data_reader),
+            Box::new(test_raw_data_writer),
+            Box::new(JsonRequestSerializer),
+            Box::new(JsonResponseParser),
+        );
+
+        // THEN
+        let response = client.run_request(&test_request).await.unwrap();
+        assert_eq!(StatusCode::Success, response.status);
+    }
+
+    mock! {
+        pub AsyncIpcReader {}
+        #[async_trait::async_trait]
+        impl AsyncIpcReader<Vec<u8>, IpcError> for AsyncIpcReader {
+            async fn read_data(&mut self) -> Result<Vec<u8>, IpcError>;
+        }
+    }
+    mock! {
+        pub AsyncIpcWriter {}
+        #[async_trait::async_trait]
+        impl AsyncIpcWriter<Vec<u8>, IpcError> for AsyncIpcWriter {
+            async fn write_data(&mut self, data: &Vec<u8>) -> Result<(), IpcError>;
+        }
+    }
+}
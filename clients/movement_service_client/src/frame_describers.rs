@@ -0,0 +1,61 @@
+use prost::Message;
+use serde_json::Value;
+use traffic_logging_reader_writer::FrameDescriber;
+
+use crate::proto_transformers::pb;
+
+pub struct ProtoRequestDescriber;
+
+impl FrameDescriber for ProtoRequestDescriber {
+    fn describe(&self, frame: &[u8]) -> String {
+        match pb::MovementApiRequest::decode(frame) {
+            Ok(request) => format!("{request:?}"),
+            Err(err) => format!("not a movement request: {err}"),
+        }
+    }
+}
+
+pub struct ProtoResponseDescriber;
+
+impl FrameDescriber for ProtoResponseDescriber {
+    fn describe(&self, frame: &[u8]) -> String {
+        match pb::MovementApiResponse::decode(frame) {
+            Ok(response) => format!("{response:?}"),
+            Err(err) => format!("not a movement response: {err}"),
+        }
+    }
+}
+
+/// Describes both requests and responses, they are self-describing JSON objects.
+pub struct JsonDescriber;
+
+impl FrameDescriber for JsonDescriber {
+    fn describe(&self, frame: &[u8]) -> String {
+        match serde_json::from_slice::<Value>(frame) {
+            Ok(json_value) => json_value.to_string(),
+            Err(err) => format!("not a JSON document: {err}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanity() {
+        // GIVEN
+        let test_response = pb::MovementApiResponse {
+            status: pb::StatusCode::Failure as i32,
+            message: "out of range".into(),
+            request_id: 3,
+        };
+
+        // THEN
+        let description = ProtoResponseDescriber.describe(&test_response.encode_to_vec());
+        assert!(description.contains("out of range"));
+        assert!(description.contains("request_id: 3"));
+        assert!(JsonDescriber.describe(b"{\"status\": \"SUCCESS\"}").contains("SUCCESS"));
+        assert!(JsonDescriber.describe(b"\x01\x02").starts_with("not a JSON document"));
+    }
+}
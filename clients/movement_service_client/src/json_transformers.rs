@@ -2,7 +2,7 @@ use serde_json::{json, Value};
 
 use movement_data::*;
 
-pub use crate::DataTransformer;
+pub use crate::{DataTransformer, TransformError};
 
 pub struct JsonRequestSerializer;
 
@@ -78,14 +78,12 @@ impl JsonRequestSerializer {
     }
 }
 
-impl DataTransformer<MovementApiRequest, Vec<u8>, String> for JsonRequestSerializer {
-    fn transform(&self, input: &MovementApiRequest) -> Result<Vec<u8>, String> {
-        let mut json_val = Self::serialize_request_data(input);
-        json_val["request_type"] = Self::serialize_request_type(input);
-        let json_string = match serde_json::to_string(&json_val) {
-            Ok(str_val) => str_val,
-            Err(err) => return Err(err.to_string()),
-        };
+impl DataTransformer<Correlated<MovementApiRequest>, Vec<u8>, TransformError> for JsonRequestSerializer {
+    fn transform(&self, input: &Correlated<MovementApiRequest>) -> Result<Vec<u8>, TransformError> {
+        let mut json_val = Self::serialize_request_data(&input.payload);
+        json_val["request_type"] = Self::serialize_request_type(&input.payload);
+        json_val["request_id"] = json!(input.request_id);
+        let json_string = serde_json::to_string(&json_val)?;
         Ok(json_string.into_bytes())
     }
 }
@@ -93,42 +91,146 @@ impl DataTransformer<MovementApiRequest, Vec<u8>, String> for JsonRequestSeriali
 pub struct JsonResponseParser;
 
 impl JsonResponseParser {
-    fn parse_result(json_data: &Value) -> Result<StatusCode, String> {
+    fn parse_result(json_data: &Value) -> Result<StatusCode, TransformError> {
         let Some(status) = json_data.get("status") else {
-            return Err("missing status field".to_string());
+            return Err(TransformError::InvalidMessage("missing status field".to_string()));
         };
         let Some(status) = status.as_str() else {
-            return Err("result field has wrong format".to_string());
+            return Err(TransformError::InvalidMessage("result field has wrong format".to_string()));
         };
         match status {
             "SUCCESS" => Ok(StatusCode::Success),
             "FAILURE" => Ok(StatusCode::Error),
-            _ => Err(format!("unsupported result value: {}", status)),
+            _ => Err(TransformError::InvalidMessage(format!("unsupported result value: {}", status))),
         }
     }
 
-    fn parse_message(json_data: &Value) -> Result<Option<String>, String> {
+    fn parse_request_id(json_data: &Value) -> Result<u32, TransformError> {
+        let Some(request_id) = json_data.get("request_id") else {
+            return Err(TransformError::InvalidMessage("missing request_id field".to_string()));
+        };
+        request_id
+            .as_u64()
+            .and_then(|request_id| u32::try_from(request_id).ok())
+            .ok_or_else(|| TransformError::InvalidMessage("request_id field has wrong format".to_string()))
+    }
+
+    fn parse_message(json_data: &Value) -> Result<Option<String>, TransformError> {
         let message_opt = json_data.get("message");
         if message_opt.is_none() {
             return Ok(None);
         }
         let Some(message) = message_opt.unwrap().as_str() else {
-            return Err("message field has wrong format".to_string());
+            return Err(TransformError::InvalidMessage("message field has wrong format".to_string()));
         };
         Ok(Some(message.to_string()))
     }
 }
 
-impl DataTransformer<Vec<u8>, MovementApiResponse, String> for JsonResponseParser {
-    fn transform(&self, input: &Vec<u8>) -> Result<MovementApiResponse, String> {
-        let json_val: Value = serde_json::from_slice(input).map_err(|err| err.to_string())?;
-        Ok(MovementApiResponse {
-            status: Self::parse_result(&json_val)?,
-            message: Self::parse_message(&json_val)?,
+impl DataTransformer<Vec<u8>, Correlated<MovementApiResponse>, TransformError> for JsonResponseParser {
+    fn transform(&self, input: &Vec<u8>) -> Result<Correlated<MovementApiResponse>, TransformError> {
+        let json_val: Value = serde_json::from_slice(input)?;
+        Ok(Correlated {
+            request_id: Self::parse_request_id(&json_val)?,
+            payload: MovementApiResponse {
+                status: Self::parse_result(&json_val)?,
+                message: Self::parse_message(&json_val)?,
+            },
+        })
+    }
+}
+
+/// Decodes the requests on the movement service side, e.g. in a simulator.
+pub struct JsonRequestParser;
+
+impl JsonRequestParser {
+    fn parse_config_data(json_data: &Value) -> Result<MovementApiRequest, TransformError> {
+        let mut axes_configs = std::collections::HashMap::new();
+        for (axis, axis_name) in [(Axis::X, "x"), (Axis::Y, "y"), (Axis::Z, "z")] {
+            let axis_data = Self::field(json_data, axis_name)?;
+            let stepper_data = Self::field(axis_data, "stepper_cfg")?;
+            let directions_mapping = serde_json::from_value(Self::field(axis_data, "directions_mapping")?.clone())?;
+            axes_configs.insert(axis, AxisConfig {
+                stepper_config: PicoStepperConfig {
+                    enable_pin: Self::u32_field(stepper_data, "enable_pin")?,
+                    step_pin: Self::u32_field(stepper_data, "step_pin")?,
+                    dir_pin: Self::u32_field(stepper_data, "dir_pin")?,
+                    hold_time_us: Self::u32_field(stepper_data, "hold_time_us")?,
+                },
+                step_length: Self::f32_field(axis_data, "step_length")?,
+                directions_mapping,
+            });
+        }
+        Ok(MovementApiRequest::Config { axes_configs })
+    }
+
+    fn parse_linear_data(json_data: &Value) -> Result<MovementApiRequest, TransformError> {
+        let destination = Self::field(json_data, "destination")?;
+        Ok(MovementApiRequest::LinearMovement {
+            destination: Vector::new(
+                Self::f32_field(destination, "x")?,
+                Self::f32_field(destination, "y")?,
+                Self::f32_field(destination, "z")?,
+            ),
+            speed: Self::f32_field(json_data, "speed")?,
+        })
+    }
+
+    fn field<'a>(json_data: &'a Value, name: &str) -> Result<&'a Value, TransformError> {
+        json_data
+            .get(name)
+            .ok_or_else(|| TransformError::InvalidMessage(format!("missing {} field", name)))
+    }
+
+    fn f32_field(json_data: &Value, name: &str) -> Result<f32, TransformError> {
+        Self::field(json_data, name)?
+            .as_f64()
+            .map(|value| value as f32)
+            .ok_or_else(|| TransformError::InvalidMessage(format!("{} field has wrong format", name)))
+    }
+
+    fn u32_field(json_data: &Value, name: &str) -> Result<u32, TransformError> {
+        Self::field(json_data, name)?
+            .as_u64()
+            .and_then(|value| u32::try_from(value).ok())
+            .ok_or_else(|| TransformError::InvalidMessage(format!("{} field has wrong format", name)))
+    }
+}
+
+impl DataTransformer<Vec<u8>, Correlated<MovementApiRequest>, TransformError> for JsonRequestParser {
+    fn transform(&self, input: &Vec<u8>) -> Result<Correlated<MovementApiRequest>, TransformError> {
+        let json_val: Value = serde_json::from_slice(input)?;
+        let request_type = Self::field(&json_val, "request_type")?.as_str();
+        let payload = match request_type {
+            Some("CONFIG") => Self::parse_config_data(&json_val)?,
+            Some("LINEAR_MOVEMENT") => Self::parse_linear_data(&json_val)?,
+            _ => return Err(TransformError::InvalidMessage(format!("unsupported request_type value: {:?}", request_type))),
+        };
+        Ok(Correlated {
+            request_id: JsonResponseParser::parse_request_id(&json_val)?,
+            payload,
         })
     }
 }
 
+pub struct JsonResponseSerializer;
+
+impl DataTransformer<Correlated<MovementApiResponse>, Vec<u8>, TransformError> for JsonResponseSerializer {
+    fn transform(&self, input: &Correlated<MovementApiResponse>) -> Result<Vec<u8>, TransformError> {
+        let mut json_val = json!({
+            "request_id": input.request_id,
+            "status": match input.payload.status {
+                StatusCode::Success => "SUCCESS",
+                StatusCode::Error => "FAILURE",
+            },
+        });
+        if let Some(message) = &input.payload.message {
+            json_val["message"] = json!(message);
+        }
+        Ok(serde_json::to_vec(&json_val)?)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use movement_data::Vector;
@@ -144,13 +246,58 @@ mod test {
         };
         let mut expected_value = JsonRequestSerializer::serialize_request_data(&test_request);
         expected_value["request_type"] = JsonRequestSerializer::serialize_request_type(&test_request);
+        expected_value["request_id"] = json!(3);
 
         // WHEN
         let request_serializer = JsonRequestSerializer;
 
         // THEN
-        let serial_request = request_serializer.transform(&test_request).unwrap();
+        let serial_request = request_serializer.transform(&Correlated { request_id: 3, payload: test_request }).unwrap();
         let parsed_serial_request: Value = serde_json::from_slice(&serial_request).unwrap();
         assert_eq!(expected_value, parsed_serial_request);
     }
+
+    #[test]
+    fn json_response_parse_sanity() {
+        // GIVEN
+        let test_response = json!({ "request_id": 5, "status": "FAILURE", "message": "out of range" });
+        let test_response_no_id = json!({ "status": "SUCCESS" });
+
+        // WHEN
+        let response_parser = JsonResponseParser;
+
+        // THEN
+        let response = response_parser.transform(&serde_json::to_vec(&test_response).unwrap()).unwrap();
+        assert_eq!(5, response.request_id);
+        assert_eq!(StatusCode::Error, response.payload.status);
+        assert_eq!(Some("out of range".to_string()), response.payload.message);
+        assert!(response_parser.transform(&serde_json::to_vec(&test_response_no_id).unwrap()).is_err());
+    }
+
+    #[test]
+    fn json_request_parse_sanity() {
+        // GIVEN
+        let test_request = MovementApiRequest::LinearMovement {
+            destination: Vector::new(1.0, 2.0, 3.0),
+            speed: 4.5,
+        };
+        let serial_request = JsonRequestSerializer.transform(&Correlated { request_id: 6, payload: test_request }).unwrap();
+
+        // WHEN
+        let request_parser = JsonRequestParser;
+
+        // THEN
+        let request = request_parser.transform(&serial_request).unwrap();
+        assert_eq!(6, request.request_id);
+        let MovementApiRequest::LinearMovement { destination, speed } = request.payload else {
+            panic!("expected a linear movement request");
+        };
+        assert_eq!(4.5, speed);
+        assert_eq!(2.0, *destination.get(&Axis::Y));
+        let serial_response = JsonResponseSerializer
+            .transform(&Correlated { request_id: 6, payload: MovementApiResponse { status: StatusCode::Error, message: None } })
+            .unwrap();
+        let response = JsonResponseParser.transform(&serial_response).unwrap();
+        assert_eq!((6, StatusCode::Error, None), (response.request_id, response.payload.status, response.payload.message));
+    }
 }
\ No newline at end of file
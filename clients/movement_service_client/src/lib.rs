@@ -1,21 +1,30 @@
-use ipc::{IpcReader, IpcWriter};
+use std::ops::ControlFlow;
 
-pub use client::ServiceClient;
-pub use movement_data::{MovementApiRequest, MovementApiResponse};
+use ipc::{IpcError, IpcReader, IpcTransactor, IpcWriter};
 
-pub type RequestSerializer = dyn DataTransformer<MovementApiRequest, Vec<u8>, String>;
-pub type ResponseParser = dyn DataTransformer<Vec<u8>, MovementApiResponse, String>;
-pub type RawDataReader = dyn IpcReader<Vec<u8>, String>;
-pub type RawDataWriter = dyn IpcWriter<Vec<u8>, String>;
+pub use client::{ClientError, ServiceClient};
+pub use movement_data::{Correlated, MovementApiRequest, MovementApiResponse};
 
-pub use json_transformers::{JsonRequestSerializer, JsonResponseParser};
-pub use proto_transformers::{ProtoRequestSerializer, ProtoResponseParser};
+pub type RequestSerializer = dyn DataTransformer<Correlated<MovementApiRequest>, Vec<u8>, TransformError> + Send;
+pub type ResponseParser = dyn DataTransformer<Vec<u8>, Correlated<MovementApiResponse>, TransformError> + Send;
+pub type RawDataReader = dyn IpcReader<Vec<u8>, IpcError> + Send;
+pub type RawDataWriter = dyn IpcWriter<Vec<u8>, IpcError> + Send;
+pub type RawDataTransactor = dyn IpcTransactor<Vec<u8>, IpcError> + Send;
+
+pub use json_transformers::{JsonRequestParser, JsonRequestSerializer, JsonResponseParser, JsonResponseSerializer};
+pub use proto_transformers::{ProtoRequestParser, ProtoRequestSerializer, ProtoResponseParser, ProtoResponseSerializer};
+pub use pipelined_client::{PipelinedMovementServiceClient, RequestResult};
+pub use frame_describers::{JsonDescriber, ProtoRequestDescriber, ProtoResponseDescriber};
+#[cfg(feature = "async")]
+pub use async_client::{
+    AsyncMovementServiceClient, AsyncRawDataReader, AsyncRawDataWriter, AsyncRequestSerializer, AsyncResponseParser
+};
+#[cfg(feature = "async")]
+pub use client::AsyncServiceClient;
 
 pub struct MovementServiceClient {
-    raw_data_reader:        Box<RawDataReader>,
-    raw_data_writer:        Box<RawDataWriter>,
-    request_serializer:     Box<RequestSerializer>,
-    response_parser:        Box<ResponseParser>,
+    raw_data_transactor:    Box<RawDataTransactor>,
+    codec:                  CorrelatedCodec,
 }
 
 impl MovementServiceClient {
@@ -25,39 +34,172 @@ impl MovementServiceClient {
         request_serializer:     Box<RequestSerializer>,
         response_parser:        Box<ResponseParser>,
     ) -> Self {
+        Self::with_transactor(
+            Box::new(ExclusiveTransactor { raw_data_reader, raw_data_writer }),
+            request_serializer,
+            response_parser,
+        )
+    }
+
+    /// Runs every request as a single transaction, so the client can share its channel with other callers.
+    pub fn with_transactor(
+        raw_data_transactor:    Box<RawDataTransactor>,
+        request_serializer:     Box<RequestSerializer>,
+        response_parser:        Box<ResponseParser>,
+    ) -> Self {
+        Self {
+            raw_data_transactor,
+            codec: CorrelatedCodec::new(request_serializer, response_parser),
+        }
+    }
+
+    /// Total number of late responses to earlier requests thrown away while waiting for a matching one.
+    pub fn discarded_responses(&self) -> usize {
+        self.codec.discarded_responses()
+    }
+}
+
+impl ServiceClient<MovementApiRequest, MovementApiResponse, ClientError> for MovementServiceClient {
+    fn run_request(&mut self, request: &MovementApiRequest) -> Result<MovementApiResponse, ClientError> {
+        let (request_id, serial_request) = self.codec.encode_request(request)?;
+        let mut outcome = None;
+        self.raw_data_transactor.transact(&serial_request, &mut |serial_response| {
+            let response = self.codec
+                .decode_response(&serial_response)
+                .and_then(|response| correlate_response(request_id, response));
+            match response {
+                Ok(None) => {
+                    self.codec.discard_response();
+                    ControlFlow::Continue(())
+                },
+                result => {
+                    outcome = result.transpose();
+                    ControlFlow::Break(())
+                },
+            }
+        })?;
+        // A transactor ending the transaction without a response breaks the transaction contract
+        outcome.unwrap_or_else(|| Err(ClientError::Ipc(IpcError::Unavailable("the transaction ended without a response".into()))))
+    }
+}
+
+/// Transactions over a reader and a writer owned by a single client, nothing else to keep out.
+struct ExclusiveTransactor {
+    raw_data_reader: Box<RawDataReader>,
+    raw_data_writer: Box<RawDataWriter>,
+}
+
+impl IpcTransactor<Vec<u8>, IpcError> for ExclusiveTransactor {
+    fn transact(&mut self, request: &Vec<u8>, on_response: &mut dyn FnMut(Vec<u8>) -> ControlFlow<()>) -> Result<(), IpcError> {
+        self.raw_data_writer.write_data(request)?;
+        loop {
+            if on_response(self.raw_data_reader.read_data()?).is_break() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Zero is what a protobuf peer leaving the request ID out sends, so it is never allocated.
+const FIRST_REQUEST_ID: u32 = 1;
+
+/// Serialization with request IDs allocation shared by the blocking and async clients.
+pub(crate) struct CorrelatedCodec<Serializer: ?Sized = RequestSerializer, Parser: ?Sized = ResponseParser> {
+    request_serializer:     Box<Serializer>,
+    response_parser:        Box<Parser>,
+    next_request_id:        u32,
+    discarded_responses:    usize,
+}
+
+impl<Serializer, Parser> CorrelatedCodec<Serializer, Parser>
+where
+    Serializer: DataTransformer<Correlated<MovementApiRequest>, Vec<u8>, TransformError> + ?Sized,
+    Parser: DataTransformer<Vec<u8>, Correlated<MovementApiResponse>, TransformError> + ?Sized,
+{
+    pub(crate) fn new(request_serializer: Box<Serializer>, response_parser: Box<Parser>) -> Self {
         Self {
-            raw_data_reader,
-            raw_data_writer,
             request_serializer,
             response_parser,
+            next_request_id: FIRST_REQUEST_ID,
+            discarded_responses: 0,
         }
     }
+
+    /// Serializes `request` under the next request ID and returns both.
+    pub(crate) fn encode_request(&mut self, request: &MovementApiRequest) -> Result<(u32, Vec<u8>), ClientError> {
+        let request_id = self.next_request_id;
+        self.next_request_id = self.next_request_id.checked_add(1).unwrap_or(FIRST_REQUEST_ID);
+        let serial_request = self.request_serializer
+            .transform(&Correlated { request_id, payload: request.clone() })
+            .map_err(|err| ClientError::Encode(Box::new(err)))?;
+        Ok((request_id, serial_request))
+    }
+
+    pub(crate) fn decode_response(&self, serial_response: &Vec<u8>) -> Result<Correlated<MovementApiResponse>, ClientError> {
+        self.response_parser
+            .transform(serial_response)
+            .map_err(|err| ClientError::Decode(Box::new(err)))
+    }
+
+    /// Counts a response thrown away as it answers no awaited request.
+    pub(crate) fn discard_response(&mut self) {
+        self.discarded_responses += 1;
+    }
+
+    pub(crate) fn discarded_responses(&self) -> usize {
+        self.discarded_responses
+    }
 }
 
-impl ServiceClient<MovementApiRequest, MovementApiResponse, String> for MovementServiceClient {
-    fn run_request(&mut self, request: &MovementApiRequest) -> Result<MovementApiResponse, String> {
-        let serial_request = self.request_serializer.transform(request)?;
-        self.raw_data_writer.write_data(&serial_request)?;
-        let serial_response = self.raw_data_reader.read_data()?;
-        let response = self.response_parser.transform(&serial_response)?;
-        Ok(response)
+/// Returns the response payload if it answers `request_id`, `None` if it answers one of the earlier requests.
+pub(crate) fn correlate_response(
+    request_id: u32,
+    response: Correlated<MovementApiResponse>,
+) -> Result<Option<MovementApiResponse>, ClientError> {
+    if response.request_id == request_id {
+        return Ok(Some(response.payload));
     }
+    // IDs wrap around, so "earlier" means within half of the ID range behind the current request
+    if request_id.wrapping_sub(response.request_id) <= u32::MAX / 2 {
+        return Ok(None);
+    }
+    Err(ClientError::UnexpectedResponse { request_id, response_id: response.request_id })
 }
 
 pub trait DataTransformer<Input, Output, Error> {
     fn transform(&self, input: &Input) -> Result<Output, Error>;
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum TransformError {
+    #[error("JSON error")]
+    Json(#[from] serde_json::Error),
+    #[error("protobuf error")]
+    Proto(#[from] prost::DecodeError),
+    #[error("invalid message: {0}")]
+    InvalidMessage(String),
+}
+
 mod json_transformers;
 mod proto_transformers;
+mod pipelined_client;
+mod frame_describers;
+#[cfg(feature = "async")]
+mod async_client;
 
 #[cfg(test)]
 mod test {
-    use std::collections::HashMap;
+    use std::collections::{HashMap, VecDeque};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
 
     use super::*;
     use mockall::mock;
     use serde_json::json;
+    use serialport::TTYPort;
+    use uart_port::{UartPort, UartTransactor};
+    use uart_sized_package_reader_writer::{DefaultSizeDecoder, DefaultSizeEncoder, UartSizedPackageReader, UartSizedPackageWriter};
     use movement_data::Vector;
 
     #[test]
@@ -85,21 +227,27 @@ mod test {
             destination: Vector::new(1.0, 2.0, 3.0),
             speed: 4.0,
         };
+        let written_request_ids = Arc::new(Mutex::new(VecDeque::new()));
         let mut test_raw_data_reader = MockIpcReader::default();
+        let request_ids = written_request_ids.clone();
         test_raw_data_reader
             .expect_read_data()
             .returning(move || {
                 let json_response = json!({
-                    "result": "SUCCESS",
+                    "request_id": request_ids.lock().unwrap().pop_front(),
+                    "status": "SUCCESS",
                 });
                 let serial_response = serde_json::to_vec(&json_response).unwrap();
                 Ok(serial_response)
             });
         let mut test_raw_data_writer = MockIpcWriter::default();
+        let request_ids = written_request_ids.clone();
         test_raw_data_writer
             .expect_write_data()
-            .returning(|data| {         
+            .returning(move |data| {         
                 println!("Writing data: {:?}", std::str::from_utf8(data).unwrap());
+                let json_request: serde_json::Value = serde_json::from_slice(data).unwrap();
+                request_ids.lock().unwrap().push_back(json_request["request_id"].clone());
                 Ok(())
             });
         // WHEN
@@ -118,16 +266,145 @@ mod test {
         assert!(response.is_ok());
     }
 
+    #[test]
+    fn stale_response_discarded() {
+        // GIVEN
+        let test_request = MovementApiRequest::LinearMovement {
+            destination: Vector::new(1.0, 2.0, 3.0),
+            speed: 4.0,
+        };
+        let mut test_responses = VecDeque::from([
+            Err(IpcError::Timeout),
+            Ok(json!({ "request_id": 1, "status": "FAILURE" })),
+            Ok(json!({ "request_id": 2, "status": "SUCCESS" })),
+            Ok(json!({ "request_id": 7, "status": "SUCCESS" })),
+        ]);
+        let mut test_raw_data_reader = MockIpcReader::default();
+        test_raw_data_reader
+            .expect_read_data()
+            .returning(move || {
+                test_responses
+                    .pop_front()
+                    .unwrap()
+                    .map(|json_response| serde_json::to_vec(&json_response).unwrap())
+            });
+        let mut test_raw_data_writer = MockIpcWriter::default();
+        test_raw_data_writer
+            .expect_write_data()
+            .returning(|_| Ok(()));
+
+        // WHEN
+        let mut client = MovementServiceClient::new(
+            Box::new(test_raw_data_reader),
+            Box::new(test_raw_data_writer),
+            Box::new(JsonRequestSerializer),
+            Box::new(JsonResponseParser),
+        );
+
+        // THEN
+        assert!(client.run_request(&test_request).unwrap_err().is_timeout());
+        let response = client.run_request(&test_request).unwrap();
+        assert_eq!(movement_data::StatusCode::Success, response.status);
+        assert_eq!(1, client.discarded_responses());
+        let response = client.run_request(&test_request);
+        assert!(matches!(response, Err(ClientError::UnexpectedResponse { request_id: 3, response_id: 7 })));
+    }
+
+    #[test]
+    fn transaction_without_response() {
+        // GIVEN
+        let test_request = MovementApiRequest::LinearMovement {
+            destination: Vector::new(1.0, 2.0, 3.0),
+            speed: 4.0,
+        };
+
+        // WHEN
+        let mut client = MovementServiceClient::with_transactor(
+            Box::new(SilentTransactor),
+            Box::new(JsonRequestSerializer),
+            Box::new(JsonResponseParser),
+        );
+
+        // THEN
+        assert!(matches!(client.run_request(&test_request), Err(ClientError::Ipc(IpcError::Unavailable(_)))));
+    }
+
+    #[test]
+    fn shared_port_transactions() {
+        // GIVEN
+        let test_callers_number = 2;
+        let test_requests_number = 5;
+        let (master, slave) = TTYPort::pair().unwrap();
+        let mcu_port = UartPort::from_serial_port(Box::new(master));
+        let shared_port = UartPort::from_serial_port(Box::new(slave));
+        let fake_mcu = thread::spawn(move || {
+            let (mut reader, mut writer) = generate_uart_reader_writer(&mcu_port);
+            for _ in 0..test_callers_number * test_requests_number {
+                let json_request: serde_json::Value = serde_json::from_slice(&reader.read_data().unwrap()).unwrap();
+                thread::sleep(Duration::from_millis(2));
+                let json_response = json!({ "request_id": json_request["request_id"], "status": "SUCCESS" });
+                writer.write_data(&serde_json::to_vec(&json_response).unwrap()).unwrap();
+            }
+            // A hung up pty fails reads even with unread data, so the port lives until the callers are done
+            mcu_port
+        });
+
+        // WHEN
+        let callers: Vec<_> = (0..test_callers_number)
+            .map(|_| {
+                let (reader, writer) = generate_uart_reader_writer(&shared_port);
+                let transactor = UartTransactor::new(&shared_port, reader, writer);
+                thread::spawn(move || {
+                    let mut client = MovementServiceClient::with_transactor(
+                        Box::new(transactor),
+                        Box::new(JsonRequestSerializer),
+                        Box::new(JsonResponseParser),
+                    );
+                    let test_request = MovementApiRequest::LinearMovement {
+                        destination: Vector::new(1.0, 2.0, 3.0),
+                        speed: 4.0,
+                    };
+                    for _ in 0..test_requests_number {
+                        assert!(client.run_request(&test_request).is_ok());
+                    }
+                    client.discarded_responses()
+                })
+            })
+            .collect();
+
+        // THEN
+        for caller in callers {
+            assert_eq!(0, caller.join().unwrap());
+        }
+        fake_mcu.join().unwrap();
+    }
+
+    fn generate_uart_reader_writer(port: &UartPort) -> (Box<UartSizedPackageReader>, Box<UartSizedPackageWriter>) {
+        let test_preamble = b"MSG_PREAMBLE";
+        (
+            Box::new(UartSizedPackageReader::new(port, test_preamble, Box::new(DefaultSizeDecoder::new(4).unwrap()), None, 1024)),
+            Box::new(UartSizedPackageWriter::new(port, test_preamble, Box::new(DefaultSizeEncoder::new(4).unwrap()), None)),
+        )
+    }
+
+    struct SilentTransactor;
+
+    impl IpcTransactor<Vec<u8>, IpcError> for SilentTransactor {
+        fn transact(&mut self, _request: &Vec<u8>, _on_response: &mut dyn FnMut(Vec<u8>) -> ControlFlow<()>) -> Result<(), IpcError> {
+            Ok(())
+        }
+    }
+
     mock! {
         pub IpcReader {}
-        impl IpcReader<Vec<u8>, String> for IpcReader {
-            fn read_data(&mut self) -> Result<Vec<u8>, String>;
+        impl IpcReader<Vec<u8>, IpcError> for IpcReader {
+            fn read_data(&mut self) -> Result<Vec<u8>, IpcError>;
         }
     }
     mock! {
         pub IpcWriter {}
-        impl IpcWriter<Vec<u8>, String> for IpcWriter {
-            fn write_data(&mut self, data: &Vec<u8>) -> Result<(), String>;
+        impl IpcWriter<Vec<u8>, IpcError> for IpcWriter {
+            fn write_data(&mut self, data: &Vec<u8>) -> Result<(), IpcError>;
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,228 @@
+use std::collections::VecDeque;
+
+use client::ClientError;
+use movement_data::{MovementApiRequest, MovementApiResponse};
+
+use crate::{CorrelatedCodec, RawDataReader, RawDataWriter, RequestSerializer, ResponseParser};
+
+/// Outcome of a single request sent through `PipelinedMovementServiceClient`.
+#[derive(Debug)]
+pub struct RequestResult {
+    pub request_id: u32,
+    pub response: Result<MovementApiResponse, ClientError>,
+}
+
+/// Keeps up to `window_size` requests in flight and pairs the responses with them by request ID.
+/// A transport or decoding failure can't be tied to a particular response, so it fails the oldest request in flight.
+pub struct PipelinedMovementServiceClient {
+    raw_data_reader:        Box<RawDataReader>,
+    raw_data_writer:        Box<RawDataWriter>,
+    codec:                  CorrelatedCodec,
+    window_size:            usize,
+    in_flight:              VecDeque<u32>,
+    completed:              VecDeque<RequestResult>,
+}
+
+impl PipelinedMovementServiceClient {
+    pub fn new(
+        raw_data_reader:        Box<RawDataReader>,
+        raw_data_writer:        Box<RawDataWriter>,
+        request_serializer:     Box<RequestSerializer>,
+        response_parser:        Box<ResponseParser>,
+        window_size:            usize,
+    ) -> Self {
+        Self {
+            raw_data_reader,
+            raw_data_writer,
+            codec: CorrelatedCodec::new(request_serializer, response_parser),
+            window_size: window_size.max(1),
+            in_flight: VecDeque::new(),
+            completed: VecDeque::new(),
+        }
+    }
+
+    /// Sends `request` once there is room in the window and returns its request ID.
+    /// Results of the requests completed while waiting for room are kept for `next_result`.
+    pub fn send_request(&mut self, request: &MovementApiRequest) -> Result<u32, ClientError> {
+        while self.in_flight.len() >= self.window_size {
+            let result = self.receive_result()?;
+            self.completed.push_back(result);
+        }
+        let (request_id, serial_request) = self.codec.encode_request(request)?;
+        self.raw_data_writer.write_data(&serial_request)?;
+        self.in_flight.push_back(request_id);
+        Ok(request_id)
+    }
+
+    /// Returns the next completed request, waiting for a response if needed, `None` once nothing is in flight.
+    pub fn next_result(&mut self) -> Result<Option<RequestResult>, ClientError> {
+        if let Some(result) = self.completed.pop_front() {
+            return Ok(Some(result));
+        }
+        if self.in_flight.is_empty() {
+            return Ok(None);
+        }
+        self.receive_result().map(Some)
+    }
+
+    /// Waits for all the requests in flight and returns their results in completion order.
+    pub fn flush(&mut self) -> Result<Vec<RequestResult>, ClientError> {
+        std::iter::from_fn(|| self.next_result().transpose()).collect()
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    /// Total number of responses thrown away as they matched no request in flight.
+    pub fn discarded_responses(&self) -> usize {
+        self.codec.discarded_responses()
+    }
+
+    fn receive_result(&mut self) -> Result<RequestResult, ClientError> {
+        loop {
+            let response = self.raw_data_reader
+                .read_data()
+                .map_err(ClientError::from)
+                .and_then(|serial_response| self.codec.decode_response(&serial_response));
+            let response = match response {
+                Ok(response) => response,
+                Err(err) => return self.fail_oldest_request(err),
+            };
+            let Some(position) = self.in_flight.iter().position(|request_id| *request_id == response.request_id) else {
+                self.codec.discard_response();
+                continue;
+            };
+            self.in_flight.remove(position);
+            return Ok(RequestResult {
+                request_id: response.request_id,
+                response: Ok(response.payload),
+            });
+        }
+    }
+
+    /// Hands `err` to the oldest request in flight, or back to the caller if there is none to tie it to.
+    fn fail_oldest_request(&mut self, err: ClientError) -> Result<RequestResult, ClientError> {
+        match self.in_flight.pop_front() {
+            Some(request_id) => Ok(RequestResult {
+                request_id,
+                response: Err(err),
+            }),
+            None => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use ipc::{IpcError, IpcReader, IpcWriter};
+    use movement_data::{StatusCode, Vector};
+    use serde_json::{json, Value};
+
+    use crate::{JsonRequestSerializer, JsonResponseParser};
+
+    use super::*;
+
+    #[test]
+    fn sanity() {
+        // GIVEN
+        let test_requests_number = 5;
+        let test_window_size = 3;
+        let fake_mcu = Arc::new(Mutex::new(FakeMcu::default()));
+
+        // WHEN
+        let mut client = generate_client(&fake_mcu, test_window_size);
+
+        // THEN
+        for i in 1..=test_requests_number {
+            assert_eq!(i, client.send_request(&generate_request()).unwrap());
+            assert!(client.in_flight() <= test_window_size);
+        }
+        let mut completed_ids: Vec<u32> = vec![];
+        while let Some(result) = client.next_result().unwrap() {
+            assert_eq!(StatusCode::Success, result.response.unwrap().status);
+            completed_ids.push(result.request_id);
+        }
+        assert_eq!((1..=test_requests_number).collect::<Vec<_>>(), completed_ids);
+        assert_eq!(test_window_size, fake_mcu.lock().unwrap().max_pending);
+    }
+
+    #[test]
+    fn out_of_order_and_lost_responses() {
+        // GIVEN
+        let fake_mcu = Arc::new(Mutex::new(FakeMcu { answer_newest_first: true, ..Default::default() }));
+
+        // WHEN
+        let mut client = generate_client(&fake_mcu, 3);
+        for _ in 0..3 {
+            client.send_request(&generate_request()).unwrap();
+        }
+        fake_mcu.lock().unwrap().pending.pop_front();
+
+        // THEN
+        let results = client.flush().unwrap();
+        assert_eq!(vec![3, 2, 1], results.iter().map(|result| result.request_id).collect::<Vec<_>>());
+        assert!(results[0].response.is_ok());
+        assert!(results[1].response.is_ok());
+        assert!(results[2].response.as_ref().unwrap_err().is_timeout());
+        assert_eq!(0, client.in_flight());
+    }
+
+    #[derive(Default)]
+    struct FakeMcu {
+        pending: VecDeque<Value>,
+        max_pending: usize,
+        answer_newest_first: bool,
+    }
+
+    struct FakeMcuReader {
+        mcu: Arc<Mutex<FakeMcu>>,
+    }
+
+    impl IpcReader<Vec<u8>, IpcError> for FakeMcuReader {
+        fn read_data(&mut self) -> Result<Vec<u8>, IpcError> {
+            let mut mcu = self.mcu.lock().unwrap();
+            let request_id = match mcu.answer_newest_first {
+                true => mcu.pending.pop_back(),
+                false => mcu.pending.pop_front(),
+            };
+            let Some(request_id) = request_id else {
+                return Err(IpcError::Timeout);
+            };
+            Ok(serde_json::to_vec(&json!({ "request_id": request_id, "status": "SUCCESS" })).unwrap())
+        }
+    }
+
+    struct FakeMcuWriter {
+        mcu: Arc<Mutex<FakeMcu>>,
+    }
+
+    impl IpcWriter<Vec<u8>, IpcError> for FakeMcuWriter {
+        fn write_data(&mut self, data: &Vec<u8>) -> Result<(), IpcError> {
+            let json_request: Value = serde_json::from_slice(data).unwrap();
+            let mut mcu = self.mcu.lock().unwrap();
+            mcu.pending.push_back(json_request["request_id"].clone());
+            mcu.max_pending = mcu.max_pending.max(mcu.pending.len());
+            Ok(())
+        }
+    }
+
+    fn generate_client(fake_mcu: &Arc<Mutex<FakeMcu>>, window_size: usize) -> PipelinedMovementServiceClient {
+        PipelinedMovementServiceClient::new(
+            Box::new(FakeMcuReader { mcu: fake_mcu.clone() }),
+            Box::new(FakeMcuWriter { mcu: fake_mcu.clone() }),
+            Box::new(JsonRequestSerializer),
+            Box::new(JsonResponseParser),
+            window_size,
+        )
+    }
+
+    fn generate_request() -> MovementApiRequest {
+        MovementApiRequest::LinearMovement {
+            destination: Vector::new(0.1, 0.2, 0.3),
+            speed: 1.0,
+        }
+    }
+}
@@ -1,11 +1,11 @@
 use std::collections::HashMap;
 
-use movement_data::{Axis, MovementApiRequest, MovementApiResponse, StatusCode};
+use movement_data::{Axis, AxisConfig, Correlated, MovementApiRequest, MovementApiResponse, PicoStepperConfig, StatusCode, Vector};
 use prost::Message;
 
-use crate::DataTransformer;
+use crate::{DataTransformer, TransformError};
 
-fn serialize_movement_request(request: &MovementApiRequest) -> Vec<u8> {
+fn serialize_movement_request(request: &MovementApiRequest, request_id: u32) -> Vec<u8> {
     match request {
         MovementApiRequest::LinearMovement { destination, speed } => {
             let target = pb::Vector {
@@ -14,6 +14,7 @@ fn serialize_movement_request(request: &MovementApiRequest) -> Vec<u8> {
                 z: *destination.get(&Axis::Z),
             };
             let pb_request = pb::MovementApiRequest {
+                request_id,
                 request: Some(pb::movement_api_request::Request::LinearMovementRequest(
                     pb::LinearMovementRequest {
                         speed: *speed,
@@ -35,6 +36,7 @@ fn serialize_movement_request(request: &MovementApiRequest) -> Vec<u8> {
                 z: *rotation_center.get(&Axis::Z),
             };
             let pb_request = pb::MovementApiRequest {
+                request_id,
                 request: Some(pb::movement_api_request::Request::RotationMovementRequest(
                     pb::RotationMovementRequest {
                         speed: *speed,
@@ -56,6 +58,7 @@ fn serialize_movement_request(request: &MovementApiRequest) -> Vec<u8> {
                 z_axis_cfg: Some(z_cfg),
             };
             let pb_request = pb::MovementApiRequest {
+                request_id,
                 request: Some(
                     pb::movement_api_request::Request::ConfigRequest(
                         pb::ConfigRequest {
@@ -101,45 +104,150 @@ fn directions_mapping_to_pb(directions_mapping: &HashMap<String, String>,
     }
 }
 
-fn parse_movement_response(data: &[u8]) -> Result<MovementApiResponse, String> {
-    let pb_response = pb::MovementApiResponse::decode(data)
-        .map_err(|e| format!("failed to decode response: {}", e))?;
+fn parse_movement_response(data: &[u8]) -> Result<Correlated<MovementApiResponse>, TransformError> {
+    let pb_response = pb::MovementApiResponse::decode(data)?;
     let pb_status = pb::StatusCode::try_from(pb_response.status)
-        .map_err(|e| format!("failed to convert status code: {}", e))?;
+        .map_err(|e| TransformError::InvalidMessage(format!("failed to convert status code: {}", e)))?;
     let status = match pb_status {
         pb::StatusCode::Success => StatusCode::Success,
         pb::StatusCode::Failure => StatusCode::Error,
     };
-    Ok(MovementApiResponse {
-        status,
-        message: pb_response.message.into(),
+    Ok(Correlated {
+        request_id: parse_request_id(pb_response.request_id)?,
+        payload: MovementApiResponse {
+            status,
+            message: pb_response.message.into(),
+        },
+    })
+}
+
+fn parse_movement_request(data: &[u8]) -> Result<Correlated<MovementApiRequest>, TransformError> {
+    let pb_request = pb::MovementApiRequest::decode(data)?;
+    let payload = match pb_request.request {
+        Some(pb::movement_api_request::Request::LinearMovementRequest(linear_request)) => MovementApiRequest::LinearMovement {
+            destination: vector_from_pb(linear_request.target, "target")?,
+            speed: linear_request.speed,
+        },
+        Some(pb::movement_api_request::Request::RotationMovementRequest(rotation_request)) => MovementApiRequest::RotationalMovement {
+            destination: vector_from_pb(rotation_request.target, "target")?,
+            rotation_center: vector_from_pb(rotation_request.rotation_center, "rotation_center")?,
+            angle: rotation_request.angle,
+            speed: rotation_request.speed,
+        },
+        Some(pb::movement_api_request::Request::ConfigRequest(config_request)) => {
+            let axes_config = config_request.axes_config.ok_or_else(|| missing_field("axes_config"))?;
+            let axes_configs = [
+                (Axis::X, axes_config.x_axis_cfg, "x_axis_cfg"),
+                (Axis::Y, axes_config.y_axis_cfg, "y_axis_cfg"),
+                (Axis::Z, axes_config.z_axis_cfg, "z_axis_cfg"),
+            ];
+            MovementApiRequest::Config {
+                axes_configs: axes_configs
+                    .into_iter()
+                    .map(|(axis, axis_cfg, field)| Ok((axis, axis_cfg_from_pb(axis_cfg.ok_or_else(|| missing_field(field))?)?)))
+                    .collect::<Result<_, TransformError>>()?,
+            }
+        },
+        None => return Err(missing_field("request")),
+    };
+    Ok(Correlated {
+        request_id: parse_request_id(pb_request.request_id)?,
+        payload,
+    })
+}
+
+fn vector_from_pb(vector: Option<pb::Vector>, field: &str) -> Result<Vector<f32>, TransformError> {
+    let vector = vector.ok_or_else(|| missing_field(field))?;
+    Ok(Vector::new(vector.x, vector.y, vector.z))
+}
+
+fn axis_cfg_from_pb(axis_cfg: pb::AxisConfig) -> Result<AxisConfig, TransformError> {
+    let stepper_config = axis_cfg.stepper_config.ok_or_else(|| missing_field("stepper_config"))?;
+    let directions_mapping = axis_cfg.directions_mapping.ok_or_else(|| missing_field("directions_mapping"))?;
+    let stepper_dir_to_str = |direction: i32| match pb::StepperDirection::try_from(direction) {
+        Ok(pb::StepperDirection::Cw) => Ok("CW".to_string()),
+        Ok(pb::StepperDirection::Ccw) => Ok("CCW".to_string()),
+        Err(err) => Err(TransformError::InvalidMessage(format!("failed to convert stepper direction: {}", err))),
+    };
+    Ok(AxisConfig {
+        stepper_config: PicoStepperConfig {
+            enable_pin: stepper_config.enable_pin,
+            step_pin: stepper_config.step_pin,
+            dir_pin: stepper_config.dir_pin,
+            hold_time_us: stepper_config.hold_time_us,
+        },
+        step_length: axis_cfg.step_length,
+        directions_mapping: HashMap::from([
+            ("POSITIVE".to_string(), stepper_dir_to_str(directions_mapping.positive)?),
+            ("NEGATIVE".to_string(), stepper_dir_to_str(directions_mapping.negative)?),
+        ]),
     })
 }
 
+/// proto3 sends no zero values, request IDs start at 1 so zero means the peer left the ID out.
+fn parse_request_id(request_id: u32) -> Result<u32, TransformError> {
+    match request_id {
+        0 => Err(missing_field("request_id")),
+        request_id => Ok(request_id),
+    }
+}
+
+fn missing_field(field: &str) -> TransformError {
+    TransformError::InvalidMessage(format!("missing {} field", field))
+}
+
+fn serialize_movement_response(response: &MovementApiResponse, request_id: u32) -> Vec<u8> {
+    let status = match response.status {
+        StatusCode::Success => pb::StatusCode::Success,
+        StatusCode::Error => pb::StatusCode::Failure,
+    };
+    pb::MovementApiResponse {
+        status: status as i32,
+        message: response.message.clone().unwrap_or_default(),
+        request_id,
+    }
+    .encode_to_vec()
+}
+
 pub struct ProtoRequestSerializer;
 
-impl DataTransformer<MovementApiRequest, Vec<u8>, String> for ProtoRequestSerializer {
-    fn transform(&self, input: &MovementApiRequest) -> Result<Vec<u8>, String> {
-        Ok(serialize_movement_request(input))
+impl DataTransformer<Correlated<MovementApiRequest>, Vec<u8>, TransformError> for ProtoRequestSerializer {
+    fn transform(&self, input: &Correlated<MovementApiRequest>) -> Result<Vec<u8>, TransformError> {
+        Ok(serialize_movement_request(&input.payload, input.request_id))
     }
 }
 
 pub struct ProtoResponseParser;
 
-impl DataTransformer<Vec<u8>, MovementApiResponse, String> for ProtoResponseParser {
-    fn transform(&self, input: &Vec<u8>) -> Result<MovementApiResponse, String> {
+impl DataTransformer<Vec<u8>, Correlated<MovementApiResponse>, TransformError> for ProtoResponseParser {
+    fn transform(&self, input: &Vec<u8>) -> Result<Correlated<MovementApiResponse>, TransformError> {
         parse_movement_response(input)
     }
 }
 
-mod pb {
+/// Decodes the requests on the movement service side, e.g. in a simulator.
+pub struct ProtoRequestParser;
+
+impl DataTransformer<Vec<u8>, Correlated<MovementApiRequest>, TransformError> for ProtoRequestParser {
+    fn transform(&self, input: &Vec<u8>) -> Result<Correlated<MovementApiRequest>, TransformError> {
+        parse_movement_request(input)
+    }
+}
+
+pub struct ProtoResponseSerializer;
+
+impl DataTransformer<Correlated<MovementApiResponse>, Vec<u8>, TransformError> for ProtoResponseSerializer {
+    fn transform(&self, input: &Correlated<MovementApiResponse>) -> Result<Vec<u8>, TransformError> {
+        Ok(serialize_movement_response(&input.payload, input.request_id))
+    }
+}
+
+pub(crate) mod pb {
     tonic::include_proto!("movement_vendor_api");
 }
 
 #[cfg(test)]
 mod tests {
-    use movement_data::Vector;
-
     use super::*;
 
     #[test]
@@ -151,7 +259,75 @@ mod tests {
         };
 
         // THEN
-        let serialized = serialize_movement_request(&test_request);
+        let serialized = serialize_movement_request(&test_request, 7);
         println!("Serialized request: {:?}", serialized);
+        assert_eq!(7, pb::MovementApiRequest::decode(serialized.as_slice()).unwrap().request_id);
+    }
+
+    #[test]
+    fn request_round_trip() {
+        // GIVEN
+        let test_axis_cfg = AxisConfig {
+            stepper_config: PicoStepperConfig { enable_pin: 1, step_pin: 2, dir_pin: 3, hold_time_us: 10 },
+            step_length: 0.005,
+            directions_mapping: HashMap::from([
+                ("POSITIVE".to_string(), "CCW".to_string()),
+                ("NEGATIVE".to_string(), "CW".to_string()),
+            ]),
+        };
+        let test_request = MovementApiRequest::Config {
+            axes_configs: HashMap::from([
+                (Axis::X, test_axis_cfg.clone()),
+                (Axis::Y, test_axis_cfg.clone()),
+                (Axis::Z, test_axis_cfg),
+            ]),
+        };
+
+        // WHEN
+        let serialized = ProtoRequestSerializer.transform(&Correlated { request_id: 9, payload: test_request }).unwrap();
+        let parsed = ProtoRequestParser.transform(&serialized).unwrap();
+
+        // THEN
+        assert_eq!(9, parsed.request_id);
+        let MovementApiRequest::Config { axes_configs } = parsed.payload else {
+            panic!("expected a config request");
+        };
+        assert_eq!(0.005, axes_configs[&Axis::Z].step_length);
+        assert_eq!(2, axes_configs[&Axis::Y].stepper_config.step_pin);
+        assert_eq!("CCW", axes_configs[&Axis::X].directions_mapping["POSITIVE"]);
+        assert!(ProtoRequestParser.transform(&pb::MovementApiRequest::default().encode_to_vec()).is_err());
+    }
+
+    #[test]
+    fn response_request_id() {
+        // GIVEN
+        let pb_response = pb::MovementApiResponse {
+            status: pb::StatusCode::Failure as i32,
+            message: "out of range".into(),
+            request_id: 42,
+        };
+
+        // WHEN
+        let response = ProtoResponseParser.transform(&pb_response.encode_to_vec()).unwrap();
+
+        // THEN
+        assert_eq!(42, response.request_id);
+        assert_eq!(StatusCode::Error, response.payload.status);
+    }
+
+    #[test]
+    fn response_without_request_id() {
+        // GIVEN
+        let pb_response = pb::MovementApiResponse {
+            status: pb::StatusCode::Success as i32,
+            message: String::new(),
+            request_id: 0,
+        };
+
+        // WHEN
+        let response = ProtoResponseParser.transform(&pb_response.encode_to_vec());
+
+        // THEN
+        assert!(matches!(response, Err(TransformError::InvalidMessage(message)) if message == "missing request_id field"));
     }
 }
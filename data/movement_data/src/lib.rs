@@ -32,6 +32,13 @@ pub struct PicoStepperConfig {
     pub hold_time_us: u32,
 }
 
+/// A request or a response together with the ID pairing them on the wire.
+#[derive(Clone, Debug)]
+pub struct Correlated<T> {
+    pub request_id: u32,
+    pub payload: T,
+}
+
 #[derive(Clone, Debug)]
 pub struct MovementApiResponse {
     pub status: StatusCode,
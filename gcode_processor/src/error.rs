@@ -0,0 +1,27 @@
+use std::error::Error;
+
+use client::ClientError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum GcodeProcessorError {
+    #[error("failed to parse G-code line: {0}")]
+    Parse(String),
+    #[error("unsupported command: {0}")]
+    UnsupportedCommand(String),
+    #[error("movement service request failed")]
+    Client(#[from] ClientError),
+    #[error("the movement service rejected the request, what: {}", .0.as_deref().unwrap_or("no details"))]
+    Rejected(Option<String>),
+    #[error("state storage failure")]
+    StateStorage(#[from] StateStorageError),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StateStorageError {
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+    #[error("failed to encode state")]
+    Encode(#[source] Box<dyn Error + Send + Sync>),
+    #[error("failed to decode state")]
+    Decode(#[source] Box<dyn Error + Send + Sync>),
+}
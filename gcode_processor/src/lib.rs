@@ -1,11 +1,13 @@
 use std::collections::HashMap;
 
-use client::ServiceClient;
+use client::{ClientError, ServiceClient};
 use movement_data::{
     Axis, AxisConfig, MovementApiRequest, MovementApiResponse, StatusCode, Vector
 };
 
-pub type MovementServiceClient = dyn ServiceClient<MovementApiRequest, MovementApiResponse, String>;
+pub use error::{GcodeProcessorError, StateStorageError};
+
+pub type MovementServiceClient = dyn ServiceClient<MovementApiRequest, MovementApiResponse, ClientError>;
 
 pub struct GcodeProcessor {
     parser: parser::GcodeParser,
@@ -13,6 +15,7 @@ pub struct GcodeProcessor {
     default_movement_speed: f32,
     movement_service_client: Box<MovementServiceClient>,
     state_storage: Box<dyn StateStorage>,
+    config_request: MovementApiRequest,
 }
 
 impl GcodeProcessor {
@@ -29,11 +32,11 @@ impl GcodeProcessor {
             default_movement_speed,
             movement_service_client,
             state_storage,
+            config_request: MovementApiRequest::Config { axes_configs: axes_configs.clone() },
         };
-        let config_request = MovementApiRequest::Config { axes_configs: axes_configs.clone() };
         let config_response = instance
             .movement_service_client
-            .run_request(&config_request)
+            .run_request(&instance.config_request)
             .expect("failed to run configuring request to the movement service");
         if config_response.status != StatusCode::Success {
             panic!("configuration request failed: {:?}", config_response.message);
@@ -41,8 +44,8 @@ impl GcodeProcessor {
         instance
     }
 
-    pub fn process(&mut self, gcode_line: &str) -> Result<(), String> {
-        let gcode_data = self.parser.parse(gcode_line)?;
+    pub fn process(&mut self, gcode_line: &str) -> Result<(), GcodeProcessorError> {
+        let gcode_data = self.parser.parse(gcode_line).map_err(GcodeProcessorError::Parse)?;
         match gcode_data.command {
             Command::G90 | Command::G91 => self.process_control_command(&gcode_data),
             _ => self.process_movement_command(&gcode_data),
@@ -53,9 +56,9 @@ impl GcodeProcessor {
         self.state_storage.read_state().unwrap()
     }
 
-    fn process_movement_command(&mut self, gcode_data: &GcodeData) -> Result<(), String> {
+    fn process_movement_command(&mut self, gcode_data: &GcodeData) -> Result<(), GcodeProcessorError> {
         let movement_request = self.generate_movement_request(gcode_data)?;
-        let movement_response = self.movement_service_client.run_request(&movement_request)?;
+        let movement_response = self.run_request(&movement_request)?;
         match movement_response.status {
             StatusCode::Success => {
                 let state = self.state_storage.read_state()?;
@@ -63,17 +66,25 @@ impl GcodeProcessor {
                 self.state_storage.write_state(&state)?;
                 Ok(())
             },
-            _ => {
-                let mut error_msg = "a failure response received from the movement service".to_string();
-                if let Some(what) = movement_response.message {
-                    error_msg = format!("{error_msg}, what: {what}");
+            _ => Err(GcodeProcessorError::Rejected(movement_response.message)),
+        }
+    }
+
+    /// A reconnected movement service has likely been reset, so the configuration is sent again before retrying.
+    fn run_request(&mut self, request: &MovementApiRequest) -> Result<MovementApiResponse, GcodeProcessorError> {
+        match self.movement_service_client.run_request(request) {
+            Err(err) if err.is_reconnect() => {
+                let config_response = self.movement_service_client.run_request(&self.config_request)?;
+                if config_response.status != StatusCode::Success {
+                    return Err(GcodeProcessorError::Rejected(config_response.message));
                 }
-                Err(error_msg)
-            }
+                Ok(self.movement_service_client.run_request(request)?)
+            },
+            result => Ok(result?),
         }
     }
 
-    fn process_control_command(&mut self, gcode_data: &GcodeData) -> Result<(), String> {
+    fn process_control_command(&mut self, gcode_data: &GcodeData) -> Result<(), GcodeProcessorError> {
         match &gcode_data.command {
             Command::G90 => {
                 let mut state = self.state_storage.read_state()?;
@@ -87,7 +98,7 @@ impl GcodeProcessor {
                 self.state_storage.write_state(&state)?;
                 Ok(())
             },
-            any_other => Err(format!("unsupported control command received: {any_other:?}")),
+            any_other => Err(GcodeProcessorError::UnsupportedCommand(format!("{any_other:?} is not a control command"))),
         }
     }
 
@@ -124,7 +135,7 @@ impl GcodeProcessor {
         target
     }
 
-    fn generate_movement_request(&self, gcode_data: &GcodeData) -> Result<MovementApiRequest, String> {
+    fn generate_movement_request(&self, gcode_data: &GcodeData) -> Result<MovementApiRequest, GcodeProcessorError> {
         match &gcode_data.command {
             Command::G00 => {
                 let state = self.state_storage.read_state()?;
@@ -144,14 +155,14 @@ impl GcodeProcessor {
                     speed,
                 })
             },
-            any_other => Err(format!("unsupported movement command received: {any_other:?}")),
+            any_other => Err(GcodeProcessorError::UnsupportedCommand(format!("{any_other:?} is not a movement command"))),
         }
     }
 }
 
 pub trait StateStorage {
-    fn read_state(&self) -> Result<GcodeProcessorState, String>;
-    fn write_state(&mut self, state: &GcodeProcessorState) -> Result<(), String>;
+    fn read_state(&self) -> Result<GcodeProcessorState, StateStorageError>;
+    fn write_state(&mut self, state: &GcodeProcessorState) -> Result<(), StateStorageError>;
 }
 
 #[derive(Clone)]
@@ -194,6 +205,7 @@ struct GcodeData {
 
 type VectorCoordinateToken = (Axis, f32);
 
+mod error;
 mod parser;
 mod vector_operations;
 
@@ -88,9 +88,88 @@ fn sanity_control() {
     assert_eq!(instance.state().coordinates_type, CoordinatesType::Absolute);
 }
 
+#[test]
+fn rejected_movement() {
+    // GIVEN
+    let mut state_storage = MockStateStorage::default();
+    let mut mock_service_client = MockServiceClient::default();
+
+    // WHEN
+    state_storage
+        .expect_read_state()
+        .returning(|| Ok(GcodeProcessorState::default()));
+    state_storage
+        .expect_write_state()
+        .never();
+    mock_service_client
+        .expect_run_request()
+        .returning(|request| match request {
+            MovementApiRequest::Config { .. } => Ok(MovementApiResponse { status: StatusCode::Success, message: None }),
+            _ => Ok(MovementApiResponse { status: StatusCode::Error, message: Some("limit switch hit".into()) }),
+        });
+    let mut instance = GcodeProcessor::new(
+        60.0,
+        30.0,
+        Box::new(mock_service_client),
+        &generate_axes_cfg(),
+        Box::new(state_storage),
+    );
+
+    // THEN
+    let result = instance.process("G01 X1.0");
+    assert!(matches!(result, Err(GcodeProcessorError::Rejected(Some(what))) if what == "limit switch hit"));
+    let result = instance.process("G02 X1.0");
+    assert!(matches!(result, Err(GcodeProcessorError::Parse(_))));
+}
+
+#[test]
+fn reconnect_restores_config() {
+    // GIVEN
+    let config_requests = std::sync::Arc::new(std::sync::Mutex::new(0));
+    let mut movement_attempts = 0;
+    let mut state_storage = MockStateStorage::default();
+    let mut mock_service_client = MockServiceClient::default();
+
+    // WHEN
+    state_storage
+        .expect_read_state()
+        .returning(|| Ok(GcodeProcessorState::default()));
+    state_storage
+        .expect_write_state()
+        .times(1)
+        .returning(|_| Ok(()));
+    let config_requests_counter = config_requests.clone();
+    mock_service_client
+        .expect_run_request()
+        .returning(move |request| match request {
+            MovementApiRequest::Config { .. } => {
+                *config_requests_counter.lock().unwrap() += 1;
+                Ok(MovementApiResponse { status: StatusCode::Success, message: None })
+            },
+            _ => {
+                movement_attempts += 1;
+                match movement_attempts {
+                    1 => Err(ClientError::Ipc(ipc::IpcError::Reconnected)),
+                    _ => Ok(MovementApiResponse { status: StatusCode::Success, message: None }),
+                }
+            },
+        });
+    let mut instance = GcodeProcessor::new(
+        60.0,
+        30.0,
+        Box::new(mock_service_client),
+        &generate_axes_cfg(),
+        Box::new(state_storage),
+    );
+
+    // THEN
+    assert!(instance.process("G01 X1.0").is_ok());
+    assert_eq!(2, *config_requests.lock().unwrap());
+}
+
 fn run_sanity_test_case<T>(gcode_line: &str, fast_speed: f32, default_speed: f32, client_callback: T)
 where
-    T: FnMut(&MovementApiRequest) -> Result<MovementApiResponse, String> + Send + 'static,
+    T: FnMut(&MovementApiRequest) -> Result<MovementApiResponse, ClientError> + Send + 'static,
 {
     // GIVEN
     let state = std::sync::Arc::new(std::sync::Mutex::new(GcodeProcessorState::default()));
@@ -179,8 +258,8 @@ fn generate_axes_cfg() -> HashMap<Axis, AxisConfig> {
 mock! {
     pub ServiceClient {}
 
-    impl ServiceClient<MovementApiRequest, MovementApiResponse, String> for ServiceClient {
-        fn run_request(&mut self, request: &MovementApiRequest) -> Result<MovementApiResponse, String>;
+    impl ServiceClient<MovementApiRequest, MovementApiResponse, ClientError> for ServiceClient {
+        fn run_request(&mut self, request: &MovementApiRequest) -> Result<MovementApiResponse, ClientError>;
     }
 }
 
@@ -188,7 +267,7 @@ mock! {
     pub StateStorage {}
 
     impl StateStorage for StateStorage {
-        fn read_state(&self) -> Result<GcodeProcessorState, String>;
-        fn write_state(&mut self, state: &GcodeProcessorState) -> Result<(), String>;
+        fn read_state(&self) -> Result<GcodeProcessorState, StateStorageError>;
+        fn write_state(&mut self, state: &GcodeProcessorState) -> Result<(), StateStorageError>;
     }
 }
\ No newline at end of file
@@ -1,23 +1,250 @@
-use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer, Serialize};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GcodeProcessorConfig {
-    pub uart_port: UartPortConfig,
+    /// Also read from `uart_port`, the key used before other transports were supported.
+    #[serde(alias = "uart_port", deserialize_with = "deserialize_transport")]
+    pub transport: TransportConfig,
+    #[serde(deserialize_with = "deserialize_uart_package")]
     pub uart_package: UartPackageConfig,
+    pub reliable_delivery: Option<ReliableDeliveryConfig>,
+    /// Dumps every frame exchanged with the MCU to stderr.
+    #[serde(default)]
+    pub log_traffic: bool,
+    /// Path of a file to record the session with the MCU to, it can be played back with the replay transport.
+    pub record_session: Option<String>,
+    /// Runs failed movement service requests again instead of stopping on the first link error.
+    pub retry: Option<RetryConfig>,
+    /// Gives up on a movement service request, retries excluded, once it takes longer than that.
+    /// The request is not cancelled, so it cannot be combined with `retry_unanswered`.
+    pub request_deadline_ms: Option<u32>,
+    pub rate_limit: Option<RateLimitConfig>,
     pub state_storage: GcodeProcessorStorageConfig,
 }
 
+/// `type` defaults to `uart`, the only transport before it was selectable.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum TransportConfig {
+    Uart(UartPortConfig),
+    Tcp(TcpPortConfig),
+    Unix(UnixSocketConfig),
+    Replay(ReplayConfig),
+    /// In-process MCU simulator speaking protobuf, neither the UART framing nor reliable delivery apply to it.
+    Simulator,
+}
+
+fn deserialize_transport<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TransportConfig, D::Error> {
+    deserialize_with_default_tag(deserializer, "type", "uart")
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct UartPortConfig {
-    pub port_name: String,
+    #[serde(flatten)]
+    pub port: UartPortLocation,
     pub baud: u32,
+    #[serde(default = "default_data_bits")]
+    pub data_bits: u8,
+    #[serde(default)]
+    pub parity: ParityType,
+    #[serde(default = "default_stop_bits")]
+    pub stop_bits: u8,
+    #[serde(default)]
+    pub flow_control: FlowControlType,
+    pub response_timeout_s: u32,
+    /// Falls back to `response_timeout_s` if not set.
+    pub write_timeout_s: Option<u32>,
+    /// Reopens the device if it disappears, e.g. when the MCU re-enumerates after a reset.
+    pub reconnect: Option<ReconnectConfig>,
+    /// Resets the MCU, waits for it and flushes stale bytes every time the device is opened.
+    pub open_sequence: Option<OpenSequenceConfig>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReconnectConfig {
+    pub initial_backoff_ms: u32,
+    pub max_backoff_ms: u32,
+    pub max_attempts: u32,
+}
+
+/// Either a device path or a selector to look the device up with udev.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum UartPortLocation {
+    Name { port_name: String },
+    Selector { port_selector: UartPortSelectorConfig },
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct UartPortSelectorConfig {
+    pub vendor_id: Option<String>,
+    pub product_id: Option<String>,
+    pub serial_number: Option<String>,
+    #[serde(default)]
+    pub properties: HashMap<String, String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OpenSequenceConfig {
+    pub reset: Option<ResetPulseConfig>,
+    pub ready_marker: Option<String>,
+    #[serde(default = "default_ready_timeout_ms")]
+    pub ready_timeout_ms: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ResetPulseConfig {
+    #[serde(default)]
+    pub dtr: bool,
+    #[serde(default)]
+    pub rts: bool,
+    pub pulse_ms: u32,
+}
+
+fn default_ready_timeout_ms() -> u32 {
+    5000
+}
+
+fn default_data_bits() -> u8 {
+    8
+}
+
+fn default_stop_bits() -> u8 {
+    1
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParityType {
+    #[default]
+    None,
+    Odd,
+    Even,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FlowControlType {
+    #[default]
+    None,
+    Hardware,
+    Software,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TcpPortConfig {
+    pub address: String,
+    pub connect_timeout_s: u32,
     pub response_timeout_s: u32,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct UartPackageConfig {
+pub struct UnixSocketConfig {
+    pub socket_path: String,
+    pub response_timeout_s: u32,
+}
+
+/// Plays a recorded session back instead of talking to the MCU, reliable delivery is not applied on top of it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReplayConfig {
+    pub session_path: String,
+    /// Delivers the responses with the recorded timing.
+    #[serde(default)]
+    pub paced: bool,
+}
+
+/// `framing` defaults to `sized`, the only framing before it was selectable.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "framing", rename_all = "lowercase")]
+pub enum UartPackageConfig {
+    Sized(SizedPackageConfig),
+    Cobs(CobsPackageConfig),
+    Slip(SlipPackageConfig),
+}
+
+fn deserialize_uart_package<'de, D: Deserializer<'de>>(deserializer: D) -> Result<UartPackageConfig, D::Error> {
+    deserialize_with_default_tag(deserializer, "framing", "sized")
+}
+
+/// Deserializes an internally tagged enum, picking `default_variant` if the `tag` field is missing.
+fn deserialize_with_default_tag<'de, D, T>(deserializer: D, tag: &str, default_variant: &str) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let mut value = serde_json::Value::deserialize(deserializer)?;
+    if let Some(fields) = value.as_object_mut() {
+        fields.entry(tag).or_insert_with(|| default_variant.into());
+    }
+    T::deserialize(value).map_err(D::Error::custom)
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SizedPackageConfig {
     pub preamble: String,
+    #[serde(default)]
+    pub size_encoding: SizeEncodingType,
     pub size_field_length: u32,
+    pub checksum: Option<ChecksumType>,
+    /// Packages announcing a larger size are dropped before their data is read, 64 KiB if not set.
+    #[serde(default = "default_max_package_size")]
+    pub max_package_size: u32,
+}
+
+fn default_max_package_size() -> u32 {
+    64 * 1024
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeEncodingType {
+    #[default]
+    LittleEndian,
+    BigEndian,
+    Varint,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CobsPackageConfig {
+    pub max_package_size: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SlipPackageConfig {
+    pub max_package_size: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChecksumType {
+    Crc16,
+    Crc32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReliableDeliveryConfig {
+    pub retransmit_timeout_ms: u32,
+    pub max_retransmissions: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RetryConfig {
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u32,
+    pub max_backoff_ms: u32,
+    /// Also retries requests which failed after they may have been written, e.g. on a timed out, garbled or unreadable
+    /// response, the MCU may then run a movement twice. Otherwise only requests which never reached the channel are retried.
+    #[serde(default)]
+    pub retry_unanswered: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    pub max_requests: u32,
+    pub period_ms: u32,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -33,15 +260,56 @@ mod tests {
     fn sanity() {
         // GIVEN
         let test_cfg = GcodeProcessorConfig {
-            uart_port: UartPortConfig {
-                port_name: "/dev/ttyACM0".into(),
+            transport: TransportConfig::Uart(UartPortConfig {
+                port: UartPortLocation::Name {
+                    port_name: "/dev/ttyACM0".into(),
+                },
                 baud: 115200,
-                response_timeout_s: 60
-            },
-            uart_package: UartPackageConfig {
+                data_bits: 8,
+                parity: ParityType::Even,
+                stop_bits: 1,
+                flow_control: FlowControlType::Hardware,
+                response_timeout_s: 60,
+                write_timeout_s: Some(5),
+                reconnect: Some(ReconnectConfig {
+                    initial_backoff_ms: 100,
+                    max_backoff_ms: 2000,
+                    max_attempts: 10,
+                }),
+                open_sequence: Some(OpenSequenceConfig {
+                    reset: Some(ResetPulseConfig {
+                        dtr: true,
+                        rts: false,
+                        pulse_ms: 100,
+                    }),
+                    ready_marker: Some("READY".into()),
+                    ready_timeout_ms: 3000,
+                }),
+            }),
+            uart_package: UartPackageConfig::Sized(SizedPackageConfig {
                 preamble: "MSG_PREAMBLE".into(),
+                size_encoding: SizeEncodingType::LittleEndian,
                 size_field_length: 4,
-            },
+                checksum: Some(ChecksumType::Crc16),
+                max_package_size: 4096,
+            }),
+            reliable_delivery: Some(ReliableDeliveryConfig {
+                retransmit_timeout_ms: 500,
+                max_retransmissions: 3,
+            }),
+            log_traffic: true,
+            record_session: Some("/usr/app/src/target/session.jsonl".into()),
+            retry: Some(RetryConfig {
+                max_attempts: 3,
+                initial_backoff_ms: 100,
+                max_backoff_ms: 1000,
+                retry_unanswered: false,
+            }),
+            request_deadline_ms: Some(10000),
+            rate_limit: Some(RateLimitConfig {
+                max_requests: 50,
+                period_ms: 1000,
+            }),
             state_storage: GcodeProcessorStorageConfig {
                 file_path: "/usr/app/src/target/state.json".into(),
             },
@@ -53,4 +321,132 @@ mod tests {
         let test_cfg_serial = test_cfg_serial.unwrap();
         println!("serialized: {test_cfg_serial}");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn transport() {
+        // GIVEN
+        let uart_cfg_serial = r#"{"type": "uart", "port_name": "/dev/ttyACM0", "baud": 115200, "response_timeout_s": 30}"#;
+        let uart_selector_cfg_serial = r#"{
+            "type": "uart", "port_selector": {"vendor_id": "2e8a"}, "baud": 115200, "response_timeout_s": 30,
+            "open_sequence": {"reset": {"dtr": true, "pulse_ms": 50}, "ready_marker": "READY"}
+        }"#;
+        let tcp_cfg_serial = r#"{"type": "tcp", "address": "192.168.4.1:2000", "connect_timeout_s": 5, "response_timeout_s": 30}"#;
+        let unix_cfg_serial = r#"{"type": "unix", "socket_path": "/run/mcu.sock", "response_timeout_s": 30}"#;
+        let replay_cfg_serial = r#"{"type": "replay", "session_path": "session.jsonl"}"#;
+        let simulator_cfg_serial = r#"{"type": "simulator"}"#;
+
+        // WHEN
+        let uart_cfg: TransportConfig = serde_json::from_str(uart_cfg_serial).unwrap();
+        let uart_selector_cfg: TransportConfig = serde_json::from_str(uart_selector_cfg_serial).unwrap();
+        let tcp_cfg: TransportConfig = serde_json::from_str(tcp_cfg_serial).unwrap();
+        let unix_cfg: TransportConfig = serde_json::from_str(unix_cfg_serial).unwrap();
+        let replay_cfg: TransportConfig = serde_json::from_str(replay_cfg_serial).unwrap();
+        let simulator_cfg: TransportConfig = serde_json::from_str(simulator_cfg_serial).unwrap();
+
+        // THEN
+        let TransportConfig::Uart(uart_cfg) = uart_cfg else {
+            panic!("expected uart transport config");
+        };
+        assert_eq!(uart_cfg.baud, 115200);
+        assert_eq!((uart_cfg.data_bits, uart_cfg.stop_bits), (8, 1));
+        assert!(matches!(uart_cfg.parity, ParityType::None));
+        assert!(matches!(uart_cfg.flow_control, FlowControlType::None));
+        assert!(uart_cfg.write_timeout_s.is_none());
+        assert!(uart_cfg.reconnect.is_none());
+        assert!(uart_cfg.open_sequence.is_none());
+        assert!(matches!(uart_cfg.port, UartPortLocation::Name { port_name } if port_name == "/dev/ttyACM0"));
+        let TransportConfig::Uart(UartPortConfig {
+            port: UartPortLocation::Selector { port_selector },
+            open_sequence: Some(open_sequence),
+            ..
+        }) = uart_selector_cfg else {
+            panic!("expected uart transport config with a port selector and an open sequence");
+        };
+        assert!(matches!(open_sequence.reset, Some(ResetPulseConfig { dtr: true, rts: false, pulse_ms: 50 })));
+        assert_eq!(open_sequence.ready_timeout_ms, 5000);
+        assert_eq!(port_selector.vendor_id.as_deref(), Some("2e8a"));
+        assert!(port_selector.product_id.is_none());
+        let TransportConfig::Tcp(tcp_cfg) = tcp_cfg else {
+            panic!("expected tcp transport config");
+        };
+        assert_eq!(tcp_cfg.address, "192.168.4.1:2000");
+        let TransportConfig::Unix(unix_cfg) = unix_cfg else {
+            panic!("expected unix transport config");
+        };
+        assert_eq!(unix_cfg.socket_path, "/run/mcu.sock");
+        assert!(matches!(replay_cfg, TransportConfig::Replay(ReplayConfig { paced: false, .. })));
+        assert!(matches!(simulator_cfg, TransportConfig::Simulator));
+    }
+
+    #[test]
+    fn uart_package_framing() {
+        // GIVEN
+        let sized_cfg_serial = r#"{"framing": "sized", "preamble": "MSG_PREAMBLE", "size_field_length": 4, "max_package_size": 4096}"#;
+        let cobs_cfg_serial = r#"{"framing": "cobs", "max_package_size": 4096}"#;
+        let slip_cfg_serial = r#"{"framing": "slip", "max_package_size": 1024}"#;
+
+        // WHEN
+        let sized_cfg: UartPackageConfig = serde_json::from_str(sized_cfg_serial).unwrap();
+        let cobs_cfg: UartPackageConfig = serde_json::from_str(cobs_cfg_serial).unwrap();
+        let slip_cfg: UartPackageConfig = serde_json::from_str(slip_cfg_serial).unwrap();
+
+        // THEN
+        let UartPackageConfig::Sized(sized_cfg) = sized_cfg else {
+            panic!("expected sized framing config");
+        };
+        assert_eq!(sized_cfg.preamble, "MSG_PREAMBLE");
+        assert!(matches!(sized_cfg.size_encoding, SizeEncodingType::LittleEndian));
+        assert!(sized_cfg.checksum.is_none());
+        assert_eq!(sized_cfg.max_package_size, 4096);
+        assert!(matches!(cobs_cfg, UartPackageConfig::Cobs(CobsPackageConfig { max_package_size: 4096 })));
+        assert!(matches!(slip_cfg, UartPackageConfig::Slip(SlipPackageConfig { max_package_size: 1024 })));
+    }
+
+    #[test]
+    fn sized_package_without_max_size() {
+        // GIVEN
+        let sized_cfg_serial = r#"{"framing": "sized", "preamble": "MSG_PREAMBLE", "size_field_length": 4}"#;
+
+        // WHEN
+        let sized_cfg: UartPackageConfig = serde_json::from_str(sized_cfg_serial).unwrap();
+
+        // THEN
+        assert!(matches!(sized_cfg, UartPackageConfig::Sized(SizedPackageConfig { max_package_size: 0x10000, .. })));
+    }
+
+    #[test]
+    fn uart_package_without_framing() {
+        // GIVEN
+        let cfg_serial = r#"{
+            "transport": {"type": "uart", "port_name": "/dev/ttyACM0", "baud": 115200, "response_timeout_s": 30},
+            "uart_package": {"preamble": "MSG_PREAMBLE", "size_field_length": 4, "max_package_size": 4096},
+            "state_storage": {"file_path": "state.json"}
+        }"#;
+
+        // WHEN
+        let cfg: GcodeProcessorConfig = serde_json::from_str(cfg_serial).unwrap();
+
+        // THEN
+        assert!(matches!(cfg.uart_package, UartPackageConfig::Sized(SizedPackageConfig { size_field_length: 4, .. })));
+    }
+
+    #[test]
+    fn legacy_uart_port() {
+        // GIVEN
+        let cfg_serial = r#"{
+            "uart_port": {"port_name": "/dev/ttyACM0", "baud": 115200, "response_timeout_s": 30},
+            "uart_package": {"framing": "sized", "preamble": "MSG_PREAMBLE", "size_field_length": 4, "max_package_size": 4096},
+            "state_storage": {"file_path": "state.json"}
+        }"#;
+
+        // WHEN
+        let cfg: GcodeProcessorConfig = serde_json::from_str(cfg_serial).unwrap();
+
+        // THEN
+        let TransportConfig::Uart(uart_cfg) = cfg.transport else {
+            panic!("expected uart transport config");
+        };
+        assert!(matches!(uart_cfg.port, UartPortLocation::Name { port_name } if port_name == "/dev/ttyACM0"));
+        assert_eq!(uart_cfg.baud, 115200);
+    }
+}
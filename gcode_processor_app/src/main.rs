@@ -1,17 +1,44 @@
+use std::error::Error;
 use std::fs::File;
 use std::{collections::HashMap};
 use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
 use std::time::Duration;
 
-use gcode_processor::{CoordinatesType, GcodeProcessor, GcodeProcessorState, StateStorage};
-use movement_data::{Axis, AxisConfig, PicoStepperConfig, Vector};
-use movement_service_client::{MovementServiceClient, ProtoRequestSerializer, ProtoResponseParser};
+use client::{ClientError, DeadlineLayer, Layer, RateLimitLayer, RetryLayer, RetryPolicy, ServiceClient};
+use gcode_processor::{CoordinatesType, GcodeProcessor, GcodeProcessorState, StateStorage, StateStorageError};
+use ipc::{ErrorChain, IpcError};
+use mcu_simulator::{McuSimulator, SimulatorReader, SimulatorWriter};
+use movement_data::{Axis, AxisConfig, MovementApiRequest, MovementApiResponse, PicoStepperConfig, Vector};
+use movement_service_client::{
+    MovementServiceClient, ProtoRequestDescriber, ProtoRequestParser, ProtoRequestSerializer, ProtoResponseDescriber,
+    ProtoResponseParser, ProtoResponseSerializer
+};
+use reliable_reader_writer::{RawFrameReader, RawFrameWriter, ReliableChannel, ReliableChannelConfig, ReliableReader, ReliableWriter};
 use serde_json::{json, Value};
-use uart_port::UartPort;
-use uart_sized_package_reader_writer::{DefaultSizeDecoder, DefaultSizeEncoder, UartSizedPackageReader, UartSizedPackageWriter};
+use session_recording_reader_writer::{RecordingReader, RecordingWriter, ReplayReader, ReplaySession, ReplayWriter, SessionRecorder};
+use tcp_sized_package_reader_writer::{TcpPort, open_tcp_port, TcpSizedPackageReader, TcpSizedPackageWriter};
+use traffic_logging_reader_writer::{TrafficLogger, TrafficLoggingReader, TrafficLoggingWriter};
+use uart_cobs_package_reader_writer::{UartCobsPackageReader, UartCobsPackageWriter};
+use uart_port::{
+    list_ports, DataBits, FlowControl, OpenSequence, Parity, ReconnectPolicy, ResetPulse, StopBits, UartPort, UartPortSelector,
+    UartPortSettings
+};
+use uart_slip_package_reader_writer::{UartSlipPackageReader, UartSlipPackageWriter};
+use unix_socket_sized_package_reader_writer::{UnixSocketPort, open_unix_socket_port, UnixSocketSizedPackageReader, UnixSocketSizedPackageWriter};
+use uart_sized_package_reader_writer::{
+    BigEndianSizeDecoder, BigEndianSizeEncoder, ChecksumCalculator, Crc16Checksum, Crc32Checksum, DefaultSizeDecoder, DefaultSizeEncoder, FramingError,
+    SizeDecoder, SizeEncoder, UartSizedPackageReader, UartSizedPackageWriter, VarintSizeDecoder, VarintSizeEncoder
+};
 
+use crate::config::{
+    ChecksumType, FlowControlType, GcodeProcessorConfig, ParityType, ReliableDeliveryConfig, SizeEncodingType, TransportConfig,
+    UartPackageConfig, UartPortConfig, UartPortLocation, UartPortSelectorConfig
+};
 use crate::configurer::JsonFileConfigurer;
-use clap::{Arg, Command};
+use clap::{Arg, ArgAction, Command};
+
+type LayeredClient = dyn ServiceClient<MovementApiRequest, MovementApiResponse, ClientError> + Send;
 
 fn main() {
     let matches = Command::new("gcode_processor_app")
@@ -27,8 +54,19 @@ fn main() {
             .required(true)
             .index(1),
         )
+        .arg(Arg::new("list_ports")
+            .long("list-ports")
+            .help("List the serial ports with their udev properties usable in a port selector and exit")
+            .action(ArgAction::SetTrue)
+            .exclusive(true)
+        )
         .get_matches();
 
+    if matches.get_flag("list_ports") {
+        print_ports();
+        std::process::exit(0);
+    }
+
     let config_path = matches.get_one::<String>("config_path").expect("required argument");
     let gcode_file = matches.get_one::<String>("gcode_file").expect("required argument");
     let configurer = JsonFileConfigurer::new(config_path);
@@ -39,35 +77,44 @@ fn main() {
             std::process::exit(-1);
         });
 
-    let uart_port = UartPort::new(
-        &config.uart_port.port_name,
-        config.uart_port.baud,
-        Duration::from_secs(config.uart_port.response_timeout_s as u64)
-    ).unwrap_or_else(|err| {
-        eprintln!("an error occured on creating UART port: {err}");
-        std::process::exit(-1);
-    });
-    let uart_reader = UartSizedPackageReader::new(
-        &uart_port,
-        config.uart_package.preamble.as_bytes(),
-        Box::new(DefaultSizeDecoder::new(config.uart_package.size_field_length as usize)),
-    );
-    let uart_writer = UartSizedPackageWriter::new(
-        &uart_port,
-        config.uart_package.preamble.as_bytes(),
-        Box::new(DefaultSizeEncoder::new(config.uart_package.size_field_length as usize)),
-    );
+    let (raw_data_reader, raw_data_writer) = create_raw_data_reader_writer(&config.transport, &config.uart_package)
+        .unwrap_or_else(|err| {
+            eprintln!("an error occured on creating transport: {}", ErrorChain(&*err));
+            std::process::exit(-1);
+        });
+    let (raw_data_reader, raw_data_writer) = match (&config.reliable_delivery, &config.transport) {
+        // Sessions are recorded above the reliable delivery and the simulator does not acknowledge frames
+        (_, TransportConfig::Replay(_) | TransportConfig::Simulator) | (None, _) => (raw_data_reader, raw_data_writer),
+        (Some(reliable_delivery_config), _) => create_reliable_reader_writer(raw_data_reader, raw_data_writer, reliable_delivery_config),
+    };
+    let (raw_data_reader, raw_data_writer) = match &config.record_session {
+        Some(session_path) => create_recording_reader_writer(raw_data_reader, raw_data_writer, session_path)
+            .unwrap_or_else(|err| {
+                eprintln!("an error occured on creating session recording at {session_path}: {}", ErrorChain(&*err));
+                std::process::exit(-1);
+            }),
+        None => (raw_data_reader, raw_data_writer),
+    };
+    let (raw_data_reader, raw_data_writer) = match config.log_traffic {
+        true => create_traffic_logging_reader_writer(raw_data_reader, raw_data_writer),
+        false => (raw_data_reader, raw_data_writer),
+    };
     let movement_service_client = MovementServiceClient::new(
-        Box::new(uart_reader),
-        Box::new(uart_writer),
+        raw_data_reader,
+        raw_data_writer,
         Box::new(ProtoRequestSerializer),
         Box::new(ProtoResponseParser),
     );
+    let movement_service_client = create_layered_client(movement_service_client, &config)
+        .unwrap_or_else(|err| {
+            eprintln!("an error occured on creating the movement service client: {}", ErrorChain(&*err));
+            std::process::exit(-1);
+        });
     let state_storage = JsonStateStorage::new(&config.state_storage.file_path);
     let mut processor = GcodeProcessor::new(
         6.0,
         3.0,
-        Box::new(movement_service_client),
+        movement_service_client,
         &generate_axes_cfg(),
         Box::new(state_storage),
     );
@@ -78,8 +125,8 @@ fn main() {
         let result = processor.process(gcode_line);
         match result {
             Ok(_) => (),
-            Err(msg) => {
-                eprintln!("gcode processor failed to process the command: {gcode_line}, what: {msg}");
+            Err(err) => {
+                eprintln!("gcode processor failed to process the command: {gcode_line}, what: {}", ErrorChain(&err));
                 std::process::exit(-1);
             },
         }
@@ -87,6 +134,304 @@ fn main() {
     std::process::exit(0);
 }
 
+fn print_ports() {
+    let ports = list_ports().unwrap_or_else(|err| {
+        eprintln!("an error occured on listing serial ports: {}", ErrorChain(&err));
+        std::process::exit(-1);
+    });
+    for port in &ports {
+        println!(
+            "{}: vendor_id={} product_id={} serial_number={}",
+            port.port_name,
+            port.vendor_id().unwrap_or("-"),
+            port.product_id().unwrap_or("-"),
+            port.serial_number().unwrap_or("-"),
+        );
+        let mut properties: Vec<_> = port.properties.iter().collect();
+        properties.sort();
+        for (name, value) in properties {
+            println!("    {name}={value}");
+        }
+    }
+}
+
+fn create_raw_data_reader_writer(
+    transport_config: &TransportConfig,
+    package_config: &UartPackageConfig,
+) -> Result<(Box<RawFrameReader>, Box<RawFrameWriter>), Box<dyn Error>> {
+    match transport_config {
+        TransportConfig::Uart(config) => {
+            let uart_settings = create_uart_settings(config)?;
+            let uart_port = match &config.port {
+                UartPortLocation::Name { port_name } => UartPort::with_settings(port_name, config.baud, &uart_settings)?,
+                UartPortLocation::Selector { port_selector } => UartPort::discover(
+                    &create_uart_port_selector(port_selector),
+                    config.baud,
+                    &uart_settings,
+                )?,
+            };
+            create_uart_reader_writer(&uart_port, package_config)
+        },
+        TransportConfig::Tcp(config) => {
+            let tcp_port = open_tcp_port(
+                &config.address,
+                Duration::from_secs(config.connect_timeout_s as u64),
+                Duration::from_secs(config.response_timeout_s as u64),
+            )?;
+            create_tcp_reader_writer(&tcp_port, package_config)
+        },
+        TransportConfig::Unix(config) => {
+            let unix_socket_port = open_unix_socket_port(
+                Path::new(&config.socket_path),
+                Duration::from_secs(config.response_timeout_s as u64),
+            )?;
+            create_unix_socket_reader_writer(&unix_socket_port, package_config)
+        },
+        TransportConfig::Replay(config) => {
+            let session = ReplaySession::load(Path::new(&config.session_path), config.paced)?;
+            Ok((Box::new(ReplayReader::new(&session)), Box::new(ReplayWriter::new(&session))))
+        },
+        TransportConfig::Simulator => {
+            let simulator = McuSimulator::new(Box::new(ProtoRequestParser), Box::new(ProtoResponseSerializer));
+            Ok((Box::new(SimulatorReader::new(&simulator)), Box::new(SimulatorWriter::new(&simulator))))
+        },
+    }
+}
+
+fn create_tcp_reader_writer(tcp_port: &TcpPort, config: &UartPackageConfig) -> Result<(Box<RawFrameReader>, Box<RawFrameWriter>), Box<dyn Error>> {
+    let UartPackageConfig::Sized(config) = config else {
+        return Err("only sized package framing is supported over TCP".into());
+    };
+    let tcp_reader = TcpSizedPackageReader::new(
+        tcp_port,
+        config.preamble.as_bytes(),
+        create_size_decoder(&config.size_encoding, config.size_field_length as usize)?,
+        config.checksum.as_ref().map(create_checksum),
+        config.max_package_size as usize,
+    );
+    let tcp_writer = TcpSizedPackageWriter::new(
+        tcp_port,
+        config.preamble.as_bytes(),
+        create_size_encoder(&config.size_encoding, config.size_field_length as usize)?,
+        config.checksum.as_ref().map(create_checksum),
+    );
+    Ok((Box::new(tcp_reader), Box::new(tcp_writer)))
+}
+
+fn create_unix_socket_reader_writer(
+    unix_socket_port: &UnixSocketPort,
+    config: &UartPackageConfig,
+) -> Result<(Box<RawFrameReader>, Box<RawFrameWriter>), Box<dyn Error>> {
+    let UartPackageConfig::Sized(config) = config else {
+        return Err("only sized package framing is supported over unix sockets".into());
+    };
+    let unix_socket_reader = UnixSocketSizedPackageReader::new(
+        unix_socket_port,
+        config.preamble.as_bytes(),
+        create_size_decoder(&config.size_encoding, config.size_field_length as usize)?,
+        config.checksum.as_ref().map(create_checksum),
+        config.max_package_size as usize,
+    );
+    let unix_socket_writer = UnixSocketSizedPackageWriter::new(
+        unix_socket_port,
+        config.preamble.as_bytes(),
+        create_size_encoder(&config.size_encoding, config.size_field_length as usize)?,
+        config.checksum.as_ref().map(create_checksum),
+    );
+    Ok((Box::new(unix_socket_reader), Box::new(unix_socket_writer)))
+}
+
+fn create_uart_reader_writer(uart_port: &UartPort, config: &UartPackageConfig) -> Result<(Box<RawFrameReader>, Box<RawFrameWriter>), Box<dyn Error>> {
+    match config {
+        UartPackageConfig::Sized(config) => {
+            let uart_reader = UartSizedPackageReader::new(
+                uart_port,
+                config.preamble.as_bytes(),
+                create_size_decoder(&config.size_encoding, config.size_field_length as usize)?,
+                config.checksum.as_ref().map(create_checksum),
+                config.max_package_size as usize,
+            );
+            let uart_writer = UartSizedPackageWriter::new(
+                uart_port,
+                config.preamble.as_bytes(),
+                create_size_encoder(&config.size_encoding, config.size_field_length as usize)?,
+                config.checksum.as_ref().map(create_checksum),
+            );
+            Ok((Box::new(uart_reader), Box::new(uart_writer)))
+        },
+        UartPackageConfig::Cobs(config) => {
+            let uart_reader = UartCobsPackageReader::new(uart_port, config.max_package_size as usize);
+            let uart_writer = UartCobsPackageWriter::new(uart_port);
+            Ok((Box::new(uart_reader), Box::new(uart_writer)))
+        },
+        UartPackageConfig::Slip(config) => {
+            let uart_reader = UartSlipPackageReader::new(uart_port, config.max_package_size as usize);
+            let uart_writer = UartSlipPackageWriter::new(uart_port);
+            Ok((Box::new(uart_reader), Box::new(uart_writer)))
+        },
+    }
+}
+
+fn create_uart_settings(config: &UartPortConfig) -> Result<UartPortSettings, Box<dyn Error>> {
+    let data_bits = match config.data_bits {
+        5 => DataBits::Five,
+        6 => DataBits::Six,
+        7 => DataBits::Seven,
+        8 => DataBits::Eight,
+        data_bits => return Err(format!("unsupported number of data bits: {data_bits}").into()),
+    };
+    let stop_bits = match config.stop_bits {
+        1 => StopBits::One,
+        2 => StopBits::Two,
+        stop_bits => return Err(format!("unsupported number of stop bits: {stop_bits}").into()),
+    };
+    if config.open_sequence.as_ref().and_then(|open_sequence_config| open_sequence_config.ready_marker.as_deref()) == Some("") {
+        return Err("the ready marker must not be empty".into());
+    }
+    let response_timeout = Duration::from_secs(config.response_timeout_s as u64);
+    Ok(UartPortSettings {
+        data_bits,
+        parity: match config.parity {
+            ParityType::None => Parity::None,
+            ParityType::Odd => Parity::Odd,
+            ParityType::Even => Parity::Even,
+        },
+        stop_bits,
+        flow_control: match config.flow_control {
+            FlowControlType::None => FlowControl::None,
+            FlowControlType::Hardware => FlowControl::Hardware,
+            FlowControlType::Software => FlowControl::Software,
+        },
+        read_timeout: response_timeout,
+        write_timeout: config.write_timeout_s.map_or(response_timeout, |timeout_s| Duration::from_secs(timeout_s as u64)),
+        reconnect: config.reconnect.as_ref().map(|reconnect_config| ReconnectPolicy {
+            initial_backoff: Duration::from_millis(reconnect_config.initial_backoff_ms as u64),
+            max_backoff: Duration::from_millis(reconnect_config.max_backoff_ms as u64),
+            max_attempts: reconnect_config.max_attempts,
+        }),
+        open_sequence: config.open_sequence.as_ref().map(|open_sequence_config| OpenSequence {
+            reset: open_sequence_config.reset.as_ref().map(|reset_config| ResetPulse {
+                dtr: reset_config.dtr,
+                rts: reset_config.rts,
+                duration: Duration::from_millis(reset_config.pulse_ms as u64),
+            }),
+            ready_marker: open_sequence_config.ready_marker.as_ref().map(|ready_marker| ready_marker.as_bytes().to_vec()),
+            ready_timeout: Duration::from_millis(open_sequence_config.ready_timeout_ms as u64),
+        }),
+    })
+}
+
+fn create_uart_port_selector(config: &UartPortSelectorConfig) -> UartPortSelector {
+    UartPortSelector {
+        vendor_id: config.vendor_id.clone(),
+        product_id: config.product_id.clone(),
+        serial_number: config.serial_number.clone(),
+        properties: config.properties.clone(),
+    }
+}
+
+fn create_reliable_reader_writer(
+    raw_data_reader: Box<RawFrameReader>,
+    raw_data_writer: Box<RawFrameWriter>,
+    config: &ReliableDeliveryConfig,
+) -> (Box<RawFrameReader>, Box<RawFrameWriter>) {
+    let channel = ReliableChannel::new(
+        raw_data_reader,
+        raw_data_writer,
+        ReliableChannelConfig {
+            retransmit_timeout: Duration::from_millis(config.retransmit_timeout_ms as u64),
+            max_retransmissions: config.max_retransmissions,
+        },
+    );
+    (Box::new(ReliableReader::new(&channel)), Box::new(ReliableWriter::new(&channel)))
+}
+
+fn create_recording_reader_writer(
+    raw_data_reader: Box<RawFrameReader>,
+    raw_data_writer: Box<RawFrameWriter>,
+    session_path: &str,
+) -> Result<(Box<RawFrameReader>, Box<RawFrameWriter>), Box<dyn Error>> {
+    let recorder = SessionRecorder::create(Path::new(session_path))?;
+    Ok((
+        Box::new(RecordingReader::new(raw_data_reader, &recorder)),
+        Box::new(RecordingWriter::new(raw_data_writer, &recorder)),
+    ))
+}
+
+/// Stacks the configured layers, innermost first: rate limit, deadline, retry.
+/// Every retry attempt then gets its own deadline and goes through the rate limit.
+fn create_layered_client(client: MovementServiceClient, config: &GcodeProcessorConfig) -> Result<Box<LayeredClient>, Box<dyn Error>> {
+    let mut client: Box<LayeredClient> = Box::new(client);
+    if let Some(rate_limit_config) = &config.rate_limit {
+        let layer = RateLimitLayer::new(rate_limit_config.max_requests, Duration::from_millis(rate_limit_config.period_ms as u64))?;
+        client = Box::new(layer.layer(client));
+    }
+    if let Some(deadline_ms) = config.request_deadline_ms {
+        client = Box::new(DeadlineLayer::new(Duration::from_millis(deadline_ms as u64)).layer(client));
+    }
+    if let Some(retry_config) = &config.retry {
+        if retry_config.retry_unanswered && config.request_deadline_ms.is_some() {
+            // A retry would be queued behind the request still running past its deadline, the MCU would get both
+            return Err("retry_unanswered cannot be combined with request_deadline_ms".into());
+        }
+        let policy = RetryPolicy {
+            max_attempts: retry_config.max_attempts as usize,
+            initial_backoff: Duration::from_millis(retry_config.initial_backoff_ms as u64),
+            max_backoff: Duration::from_millis(retry_config.max_backoff_ms as u64),
+        };
+        let retry_unanswered = retry_config.retry_unanswered;
+        let layer = RetryLayer::new(policy, move |err: &ClientError| is_transient_error(err, retry_unanswered));
+        client = Box::new(layer.layer(client));
+    }
+    Ok(client)
+}
+
+fn is_transient_error(err: &ClientError, retry_unanswered: bool) -> bool {
+    match err {
+        // The gcode processor restores the MCU config itself after a reconnect
+        ClientError::Ipc(IpcError::Reconnected) => false,
+        // The request may have been written and executed before these, running it again may repeat a movement
+        ClientError::Ipc(IpcError::Timeout | IpcError::Io(_) | IpcError::Framing(_)) | ClientError::Decode(_) => retry_unanswered,
+        // Nothing was written to the channel
+        ClientError::Ipc(IpcError::Unavailable(_)) => true,
+        ClientError::Encode(_) | ClientError::UnexpectedResponse { .. } => false,
+    }
+}
+
+fn create_traffic_logging_reader_writer(
+    raw_data_reader: Box<RawFrameReader>,
+    raw_data_writer: Box<RawFrameWriter>,
+) -> (Box<RawFrameReader>, Box<RawFrameWriter>) {
+    let logger = TrafficLogger::stderr();
+    (
+        Box::new(TrafficLoggingReader::new(raw_data_reader, &logger, Some(Box::new(ProtoResponseDescriber)))),
+        Box::new(TrafficLoggingWriter::new(raw_data_writer, &logger, Some(Box::new(ProtoRequestDescriber)))),
+    )
+}
+
+fn create_size_encoder(size_encoding: &SizeEncodingType, size_field_length: usize) -> Result<Box<dyn SizeEncoder + Send + Sync>, FramingError> {
+    Ok(match size_encoding {
+        SizeEncodingType::LittleEndian => Box::new(DefaultSizeEncoder::new(size_field_length)?),
+        SizeEncodingType::BigEndian => Box::new(BigEndianSizeEncoder::new(size_field_length)?),
+        SizeEncodingType::Varint => Box::new(VarintSizeEncoder::new()),
+    })
+}
+
+fn create_size_decoder(size_encoding: &SizeEncodingType, size_field_length: usize) -> Result<Box<dyn SizeDecoder + Send + Sync>, FramingError> {
+    Ok(match size_encoding {
+        SizeEncodingType::LittleEndian => Box::new(DefaultSizeDecoder::new(size_field_length)?),
+        SizeEncodingType::BigEndian => Box::new(BigEndianSizeDecoder::new(size_field_length)?),
+        SizeEncodingType::Varint => Box::new(VarintSizeDecoder::new()),
+    })
+}
+
+fn create_checksum(checksum_type: &ChecksumType) -> Box<dyn ChecksumCalculator + Send + Sync> {
+    match checksum_type {
+        ChecksumType::Crc16 => Box::new(Crc16Checksum::new()),
+        ChecksumType::Crc32 => Box::new(Crc32Checksum::new()),
+    }
+}
+
 fn generate_axes_cfg() -> HashMap<Axis, AxisConfig> {
     let step_length = 0.005;
     let hold_time_us = 10;
@@ -182,7 +527,7 @@ impl JsonStateStorage {
         Self { file_path: file_path.to_string() }
     }
 
-    fn write_state(file_path: &str, state: &GcodeProcessorState) -> Result<(), String> {
+    fn write_state(file_path: &str, state: &GcodeProcessorState) -> Result<(), StateStorageError> {
         let coordinates_type_json = match state.coordinates_type {
             CoordinatesType::Absolute => json!("absolute"),
             CoordinatesType::Relative => json!("relative"),
@@ -192,26 +537,23 @@ impl JsonStateStorage {
             "current_position": Self::vector_to_json_object(&state.current_position),
         });
         let json_string = serde_json::to_string(&json_data)
-            .map_err(|e| format!("failed to serialize state to JSON: {}", e))?;
-        std::fs::write(file_path, json_string)
-            .map_err(|e| format!("failed to write state to file: {}", e))
+            .map_err(|e| StateStorageError::Encode(Box::new(e)))?;
+        Ok(std::fs::write(file_path, json_string)?)
     }
 
-    fn read_state(file_path: &str) -> Result<GcodeProcessorState, String> {
-        let mut file = std::fs::File::open(file_path)
-            .map_err(|e| format!("failed to open state file: {}", e))?;
+    fn read_state(file_path: &str) -> Result<GcodeProcessorState, StateStorageError> {
+        let mut file = std::fs::File::open(file_path)?;
         let mut contents = String::new();
-        file.read_to_string(&mut contents)
-            .map_err(|e| format!("failed to read state file: {}", e))?;
+        file.read_to_string(&mut contents)?;
         let json_data: Value = serde_json::from_str(&contents)
-            .map_err(|e| format!("failed to parse state file: {}", e))?;
+            .map_err(|e| StateStorageError::Decode(Box::new(e)))?;
         let current_position_json = Self::read_json_value_from_object(&json_data, "current_position")?;
         let current_position = Self::json_object_to_vector(&current_position_json)?;
         let coordinates_type_str = Self::read_json_value_from_object(&json_data, "coordinates_type")?;
         let coordinates_type = match coordinates_type_str.as_str() {
             Some("absolute") => CoordinatesType::Absolute,
             Some("relative") => CoordinatesType::Relative,
-            _ => return Err("invalid coordinates type in state file".to_string()),
+            _ => return Err(StateStorageError::Decode("invalid coordinates type in state file".into())),
         };
         Ok(GcodeProcessorState {
             current_position,
@@ -219,19 +561,19 @@ impl JsonStateStorage {
         })
     }
 
-    fn read_json_value_from_object(json_data: &Value, key: &str) -> Result<Value, String> {
+    fn read_json_value_from_object(json_data: &Value, key: &str) -> Result<Value, StateStorageError> {
         let Some(result) = json_data.get(key) else {
-            return Err(format!("missing '{key}' in JSON data"));
+            return Err(StateStorageError::Decode(format!("missing '{key}' in JSON data").into()));
         };
         Ok(result.clone())
     }
 
-    fn json_object_to_vector(json_object: &Value) -> Result<Vector<f32>, String> {
+    fn json_object_to_vector(json_object: &Value) -> Result<Vector<f32>, StateStorageError> {
         let mut vector = Vector::default();
         for (axis_str, axis) in [("x", Axis::X), ("y", Axis::Y), ("z", Axis::Z)] {
             let value = Self::read_json_value_from_object(json_object, axis_str)?
                 .as_f64()
-                .ok_or_else(|| format!("invalid vector value for axis '{axis_str}'"))?;
+                .ok_or_else(|| StateStorageError::Decode(format!("invalid vector value for axis '{axis_str}'").into()))?;
             vector.set(&axis, value as f32);
         }
         Ok(vector)
@@ -247,14 +589,54 @@ impl JsonStateStorage {
 }
 
 impl StateStorage for JsonStateStorage {
-    fn read_state(&self) -> Result<GcodeProcessorState, String> {
+    fn read_state(&self) -> Result<GcodeProcessorState, StateStorageError> {
         Self::read_state(&self.file_path)
     }
 
-    fn write_state(&mut self, state: &GcodeProcessorState) -> Result<(), String> {
+    fn write_state(&mut self, state: &GcodeProcessorState) -> Result<(), StateStorageError> {
         Self::write_state(&self.file_path, state)
     }
 }
 
 mod config;
-mod configurer;
\ No newline at end of file
+mod configurer;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transient_errors() {
+        // GIVEN
+        let maybe_executed = [
+            ClientError::Ipc(IpcError::Timeout),
+            ClientError::Ipc(IpcError::Io(std::io::ErrorKind::BrokenPipe.into())),
+            ClientError::Ipc(IpcError::Framing(FramingError::InvalidSize("size field garbled".into()))),
+            ClientError::Decode("garbled".into()),
+        ];
+        let never_sent = ClientError::Ipc(IpcError::Unavailable("port is gone".into()));
+
+        // THEN
+        assert!(maybe_executed.iter().all(|err| !is_transient_error(err, false)));
+        assert!(maybe_executed.iter().all(|err| is_transient_error(err, true)));
+        assert!(is_transient_error(&never_sent, false));
+        assert!(!is_transient_error(&ClientError::Ipc(IpcError::Reconnected), true));
+    }
+
+    #[test]
+    fn empty_ready_marker() {
+        // GIVEN
+        let test_config: UartPortConfig = serde_json::from_value(json!({
+            "port_name": "/dev/ttyACM0",
+            "baud": 115200,
+            "response_timeout_s": 1,
+            "open_sequence": {"ready_marker": ""}
+        }))
+        .unwrap();
+
+        // WHEN
+        let result = create_uart_settings(&test_config);
+
+        // THEN
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,142 @@
+use std::process::{Command, Output};
+
+use mcu_simulator::{Fault, McuSimulator, SimulatorReader, SimulatorWriter};
+use movement_data::Axis;
+use movement_service_client::{ProtoRequestParser, ProtoResponseSerializer};
+use serde_json::json;
+use tempfile::TempDir;
+use uart_port::UartPort;
+use uart_sized_package_reader_writer::{Crc16Checksum, DefaultSizeDecoder, DefaultSizeEncoder, UartSizedPackageReader, UartSizedPackageWriter};
+use virtual_serial_port::{FakeMcu, IpcResponder, VirtualSerialPort};
+
+const TEST_PREAMBLE: &[u8] = b"MSG_PREAMBLE";
+
+#[test]
+fn gcode_file_over_virtual_serial_port() {
+    // GIVEN
+    let work_dir = tempfile::tempdir().unwrap();
+    let virtual_port = VirtualSerialPort::new().unwrap();
+    let simulator = McuSimulator::new(Box::new(ProtoRequestParser), Box::new(ProtoResponseSerializer));
+    let fake_mcu = spawn_fake_mcu(&virtual_port, &simulator);
+
+    // WHEN
+    let output = run_app(&work_dir, &virtual_port, json!({}), "G90\nG00 X1 Y2\nG01 X1.5 Z-0.25 F1.2\n");
+
+    // THEN
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(3, fake_mcu.stop().unwrap());
+    assert!(simulator.is_configured());
+    assert_eq!(2, simulator.movements().len());
+    let position_steps = simulator.position_steps();
+    assert_eq!(
+        (300, 400, -50),
+        (*position_steps.get(&Axis::X), *position_steps.get(&Axis::Y), *position_steps.get(&Axis::Z)),
+    );
+    let state: serde_json::Value = serde_json::from_slice(&std::fs::read(work_dir.path().join("state.json")).unwrap()).unwrap();
+    assert_eq!(json!(1.5), state["current_position"]["x"]);
+}
+
+#[test]
+fn retried_lost_response() {
+    // GIVEN
+    let work_dir = tempfile::tempdir().unwrap();
+    let virtual_port = VirtualSerialPort::new().unwrap();
+    let simulator = McuSimulator::new(Box::new(ProtoRequestParser), Box::new(ProtoResponseSerializer));
+    let fake_mcu = spawn_fake_mcu(&virtual_port, &simulator);
+    let request_policy = json!({
+        "retry": {"max_attempts": 2, "initial_backoff_ms": 10, "max_backoff_ms": 10, "retry_unanswered": true},
+        "rate_limit": {"max_requests": 100, "period_ms": 1000}
+    });
+
+    // WHEN
+    simulator.inject_fault(Fault::DropResponse);
+    let output = run_app(&work_dir, &virtual_port, request_policy, "G00 X1\n");
+
+    // THEN
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    // The unanswered config request was sent again
+    assert_eq!(3, fake_mcu.stop().unwrap());
+    assert_eq!(1, simulator.movements().len());
+}
+
+#[test]
+fn deadline_refused_with_retry_unanswered() {
+    // GIVEN
+    let work_dir = tempfile::tempdir().unwrap();
+    let virtual_port = VirtualSerialPort::new().unwrap();
+    let request_policy = json!({
+        "retry": {"max_attempts": 2, "initial_backoff_ms": 10, "max_backoff_ms": 10, "retry_unanswered": true},
+        "request_deadline_ms": 5000
+    });
+
+    // WHEN
+    let output = run_app(&work_dir, &virtual_port, request_policy, "G00 X1\n");
+
+    // THEN
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("retry_unanswered cannot be combined with request_deadline_ms"));
+}
+
+fn spawn_fake_mcu(virtual_port: &VirtualSerialPort, simulator: &McuSimulator) -> FakeMcu {
+    let (mcu_reader, mcu_writer) = generate_mcu_reader_writer(virtual_port.mcu_port());
+    FakeMcu::spawn(
+        mcu_reader,
+        mcu_writer,
+        Box::new(IpcResponder::new(
+            Box::new(SimulatorReader::new(simulator)),
+            Box::new(SimulatorWriter::new(simulator)),
+        )),
+    )
+}
+
+fn run_app(work_dir: &TempDir, virtual_port: &VirtualSerialPort, extra_config: serde_json::Value, gcode: &str) -> Output {
+    let config_path = work_dir.path().join("config.json");
+    let gcode_path = work_dir.path().join("test.gcode");
+    let mut config = json!({
+        "transport": {
+            "type": "uart",
+            "port_name": virtual_port.port_name(),
+            "baud": 115200,
+            "response_timeout_s": 1
+        },
+        "uart_package": {
+            "framing": "sized",
+            "preamble": "MSG_PREAMBLE",
+            "size_field_length": 4,
+            "checksum": "crc16",
+            "max_package_size": 4096
+        },
+        "state_storage": {
+            "file_path": work_dir.path().join("state.json")
+        }
+    });
+    for (key, value) in extra_config.as_object().unwrap() {
+        config[key] = value.clone();
+    }
+    std::fs::write(&config_path, config.to_string()).unwrap();
+    std::fs::write(&gcode_path, gcode).unwrap();
+    Command::new(env!("CARGO_BIN_EXE_gcode_processor_app"))
+        .arg("--config")
+        .arg(&config_path)
+        .arg(&gcode_path)
+        .output()
+        .unwrap()
+}
+
+fn generate_mcu_reader_writer(port: &UartPort) -> (Box<UartSizedPackageReader>, Box<UartSizedPackageWriter>) {
+    (
+        Box::new(UartSizedPackageReader::new(
+            port,
+            TEST_PREAMBLE,
+            Box::new(DefaultSizeDecoder::new(4).unwrap()),
+            Some(Box::new(Crc16Checksum::new())),
+            4096,
+        )),
+        Box::new(UartSizedPackageWriter::new(
+            port,
+            TEST_PREAMBLE,
+            Box::new(DefaultSizeEncoder::new(4).unwrap()),
+            Some(Box::new(Crc16Checksum::new())),
+        )),
+    )
+}
@@ -0,0 +1,95 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Failure of a single read or write over an IPC channel.
+#[derive(Debug, thiserror::Error)]
+pub enum IpcError {
+    #[error("operation timed out")]
+    Timeout,
+    #[error("I/O error")]
+    Io(#[source] io::Error),
+    #[error("framing error")]
+    Framing(#[from] FramingError),
+    #[error("channel unavailable: {0}")]
+    Unavailable(String),
+    /// The channel was lost and opened again, nothing was transferred and the peer may have lost its state.
+    #[error("channel reconnected, the peer may have been reset")]
+    Reconnected,
+}
+
+impl From<io::Error> for IpcError {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => Self::Timeout,
+            _ => Self::Io(err),
+        }
+    }
+}
+
+/// A package that was received but could not be unpacked, or could not be packed for sending.
+#[derive(Debug, thiserror::Error)]
+pub enum FramingError {
+    #[error("checksum mismatch: expected {expected:?}, received {received:?}")]
+    ChecksumMismatch { expected: Vec<u8>, received: Vec<u8> },
+    #[error("package size ({size}) exceeds the maximum allowed size ({max_size})")]
+    PackageTooLarge { size: usize, max_size: usize },
+    #[error("invalid size field: {0}")]
+    InvalidSize(String),
+    #[error("malformed package: {0}")]
+    Malformed(String),
+}
+
+/// Displays an error followed by its sources, e.g. `transport failure: I/O error: device gone`.
+/// Error messages leave their sources out, report errors through this to keep the details.
+pub struct ErrorChain<'a>(pub &'a dyn Error);
+
+impl fmt::Display for ErrorChain<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)?;
+        let mut source = self.0.source();
+        while let Some(err) = source {
+            write!(f, ": {err}")?;
+            source = err.source();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn io_error_conversion() {
+        // GIVEN
+        let timed_out = io::Error::new(io::ErrorKind::TimedOut, "no response");
+        let broken_pipe = io::Error::new(io::ErrorKind::BrokenPipe, "device gone");
+
+        // WHEN
+        let timed_out = IpcError::from(timed_out);
+        let broken_pipe = IpcError::from(broken_pipe);
+
+        // THEN
+        assert!(matches!(timed_out, IpcError::Timeout));
+        assert!(matches!(&broken_pipe, IpcError::Io(err) if err.kind() == io::ErrorKind::BrokenPipe));
+        assert!(broken_pipe.source().is_some());
+    }
+
+    #[test]
+    fn error_chain() {
+        // GIVEN
+        let broken_pipe = IpcError::from(io::Error::new(io::ErrorKind::BrokenPipe, "device gone"));
+        let bad_checksum = IpcError::from(FramingError::ChecksumMismatch { expected: vec![1], received: vec![2] });
+
+        // WHEN
+        let broken_pipe_report = ErrorChain(&broken_pipe).to_string();
+        let bad_checksum_report = ErrorChain(&bad_checksum).to_string();
+
+        // THEN
+        // Every source is reported once
+        assert_eq!("I/O error", broken_pipe.to_string());
+        assert_eq!("I/O error: device gone", broken_pipe_report);
+        assert_eq!("framing error: checksum mismatch: expected [1], received [2]", bad_checksum_report);
+    }
+}
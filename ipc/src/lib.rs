@@ -1,7 +1,31 @@
+use std::ops::ControlFlow;
+
+pub use error::{ErrorChain, FramingError, IpcError};
+
 pub trait IpcReader<Data, Error> {
     fn read_data(&mut self) -> Result<Data, Error>;
 }
 
 pub trait IpcWriter<Data, Error> {
     fn write_data(&mut self, data: &Data) -> Result<(), Error>;
-}
\ No newline at end of file
+}
+
+/// Request/response exchange that keeps other users of a shared channel out until it is over.
+pub trait IpcTransactor<Data, Error> {
+    /// Writes `request`, then passes the received data to `on_response` until it breaks.
+    fn transact(&mut self, request: &Data, on_response: &mut dyn FnMut(Data) -> ControlFlow<()>) -> Result<(), Error>;
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+pub trait AsyncIpcReader<Data, Error> {
+    async fn read_data(&mut self) -> Result<Data, Error>;
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+pub trait AsyncIpcWriter<Data: Sync, Error> {
+    async fn write_data(&mut self, data: &Data) -> Result<(), Error>;
+}
+
+mod error;
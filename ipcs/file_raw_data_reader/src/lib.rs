@@ -0,0 +1,147 @@
+use std::collections::VecDeque;
+use std::io;
+
+use ipc::{FramingError, IpcError};
+use serde_json::Value;
+
+pub type FileRawData = Vec<u8>;
+
+const SIZE_FIELD_LENGTH: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FileRawDataFormat {
+    /// A JSON array, every element is served as a separate package.
+    Json,
+    /// Packages prefixed with their size as a 4 bytes little endian number.
+    Binary,
+}
+
+/// Serves the packages queued in a file one by one, then times out as a silent peer would.
+pub struct FileRawDataReader {
+    packages: VecDeque<FileRawData>,
+}
+
+impl FileRawDataReader {
+    pub fn new(file_path: &str, format: FileRawDataFormat) -> Result<Self, IpcError> {
+        let contents = std::fs::read(file_path)?;
+        let packages = match format {
+            FileRawDataFormat::Json => parse_json_packages(&contents)?,
+            FileRawDataFormat::Binary => parse_binary_packages(&contents)?,
+        };
+        Ok(Self { packages })
+    }
+
+    pub fn remaining_packages(&self) -> usize {
+        self.packages.len()
+    }
+}
+
+impl ipc::IpcReader<FileRawData, IpcError> for FileRawDataReader {
+    fn read_data(&mut self) -> Result<FileRawData, IpcError> {
+        self.packages.pop_front().ok_or(IpcError::Timeout)
+    }
+}
+
+fn parse_json_packages(contents: &[u8]) -> Result<VecDeque<FileRawData>, IpcError> {
+    let json_data: Value = serde_json::from_slice(contents).map_err(io::Error::from)?;
+    let Value::Array(json_packages) = json_data else {
+        return Err(FramingError::Malformed("expected a JSON array of packages".into()).into());
+    };
+    json_packages
+        .iter()
+        .map(|json_package| Ok(serde_json::to_vec(json_package).map_err(io::Error::from)?))
+        .collect()
+}
+
+fn parse_binary_packages(mut contents: &[u8]) -> Result<VecDeque<FileRawData>, IpcError> {
+    let mut packages = VecDeque::new();
+    while !contents.is_empty() {
+        let Some((size_field, rest)) = contents.split_first_chunk::<SIZE_FIELD_LENGTH>() else {
+            return Err(FramingError::InvalidSize("truncated package size".into()).into());
+        };
+        let size = u32::from_le_bytes(*size_field) as usize;
+        if rest.len() < size {
+            return Err(FramingError::InvalidSize(format!("package of {size} bytes is truncated to {}", rest.len())).into());
+        }
+        let (package, rest) = rest.split_at(size);
+        packages.push_back(package.to_vec());
+        contents = rest;
+    }
+    Ok(packages)
+}
+
+#[cfg(test)]
+mod tests {
+    use file_raw_data_writer::{FileRawDataWriteMode, FileRawDataWriter};
+    use ipc::{IpcReader, IpcWriter};
+
+    use super::*;
+
+    #[test]
+    fn sanity() {
+        // GIVEN
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let test_file_path = tmp_dir.path().join("test.json");
+        std::fs::write(&test_file_path, r#"[{"request_id": 0, "status": "SUCCESS"}, {"request_id": 1, "status": "FAILURE"}]"#).unwrap();
+
+        // WHEN
+        let mut reader = FileRawDataReader::new(test_file_path.to_str().unwrap(), FileRawDataFormat::Json).unwrap();
+
+        // THEN
+        assert_eq!(2, reader.remaining_packages());
+        let package: Value = serde_json::from_slice(&reader.read_data().unwrap()).unwrap();
+        assert_eq!(package["status"], "SUCCESS");
+        let package: Value = serde_json::from_slice(&reader.read_data().unwrap()).unwrap();
+        assert_eq!(package["request_id"], 1);
+        assert!(matches!(reader.read_data(), Err(IpcError::Timeout)));
+    }
+
+    #[test]
+    fn binary() {
+        // GIVEN
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let test_file_path = tmp_dir.path().join("test.bin");
+        std::fs::write(&test_file_path, [2, 0, 0, 0, 0x08, 0x01, 0, 0, 0, 0]).unwrap();
+        let truncated_file_path = tmp_dir.path().join("truncated.bin");
+        std::fs::write(&truncated_file_path, [3, 0, 0, 0, 0x08]).unwrap();
+
+        // WHEN
+        let mut reader = FileRawDataReader::new(test_file_path.to_str().unwrap(), FileRawDataFormat::Binary).unwrap();
+
+        // THEN
+        assert_eq!(vec![0x08, 0x01], reader.read_data().unwrap());
+        assert!(reader.read_data().unwrap().is_empty());
+        assert!(matches!(reader.read_data(), Err(IpcError::Timeout)));
+        let result = FileRawDataReader::new(truncated_file_path.to_str().unwrap(), FileRawDataFormat::Binary);
+        assert!(matches!(result, Err(IpcError::Framing(FramingError::InvalidSize(_)))));
+    }
+
+    #[test]
+    fn file_scripted_session() {
+        // GIVEN
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let responses_path = tmp_dir.path().join("responses.json");
+        let requests_path = tmp_dir.path().join("requests.jsonl");
+        std::fs::write(&responses_path, r#"[{"request_id": 0, "status": "SUCCESS"}]"#).unwrap();
+
+        // WHEN
+        let mut reader = FileRawDataReader::new(responses_path.to_str().unwrap(), FileRawDataFormat::Json).unwrap();
+        let mut writer = FileRawDataWriter::with_mode(requests_path.to_str().unwrap(), FileRawDataWriteMode::JsonLines);
+
+        // THEN
+        for request_id in 0..2 {
+            writer.write_data(&serde_json::to_vec(&serde_json::json!({ "request_id": request_id })).unwrap()).unwrap();
+        }
+        let response: Value = serde_json::from_slice(&reader.read_data().unwrap()).unwrap();
+        assert_eq!(response["status"], "SUCCESS");
+        // The script is over, the second request stays unanswered
+        assert!(matches!(reader.read_data(), Err(IpcError::Timeout)));
+        let written_requests = std::fs::read_to_string(requests_path).unwrap();
+        let written_requests: Vec<Value> = written_requests
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(2, written_requests.len());
+        assert_eq!(written_requests[1]["request_id"], 1);
+    }
+}
@@ -1,23 +1,81 @@
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use ipc::{FramingError, IpcError};
 use serde_json::{json, Value};
 
 pub type FileRawData = Vec<u8>;
 
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FileRawDataWriteMode {
+    /// Replaces the file with a one-element JSON array on every write, only the last package survives.
+    #[default]
+    Json,
+    /// Appends every package as a line of JSON.
+    JsonLines,
+    /// Appends packages of any content prefixed with their size as a 4 bytes little endian number.
+    Binary,
+    /// Appends every package of any content as a line of base64.
+    Base64Lines,
+}
+
 pub struct FileRawDataWriter {
     file_path: String,
+    mode: FileRawDataWriteMode,
 }
 
 impl FileRawDataWriter {
     pub fn new(file_path: &str) -> Self {
-        Self { file_path: String::from(file_path) }
+        Self::with_mode(file_path, FileRawDataWriteMode::default())
+    }
+
+    pub fn with_mode(file_path: &str, mode: FileRawDataWriteMode) -> Self {
+        Self {
+            file_path: String::from(file_path),
+            mode,
+        }
+    }
+
+    fn append(&self, record: &[u8]) -> Result<(), IpcError> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.file_path)?;
+        Ok(file.write_all(record)?)
     }
 }
 
-impl ipc::IpcWriter<FileRawData, String> for FileRawDataWriter {
-    fn write_data(&mut self, data: &FileRawData) -> Result<(), String> {
-        let json_data: Value = serde_json::from_slice(data).map_err(|e| e.to_string())?;
-        let json_data = json!([ json_data ]);
-        let serialized = serde_json::to_vec(&json_data).map_err(|e| e.to_string())?;
-        std::fs::write(&self.file_path, &serialized).map_err(|e| e.to_string())
+impl ipc::IpcWriter<FileRawData, IpcError> for FileRawDataWriter {
+    fn write_data(&mut self, data: &FileRawData) -> Result<(), IpcError> {
+        match self.mode {
+            FileRawDataWriteMode::Json => {
+                let json_data: Value = serde_json::from_slice(data).map_err(io::Error::from)?;
+                let json_data = json!([ json_data ]);
+                let serialized = serde_json::to_vec(&json_data).map_err(io::Error::from)?;
+                Ok(std::fs::write(&self.file_path, &serialized)?)
+            },
+            FileRawDataWriteMode::JsonLines => {
+                // Re-serializing keeps a pretty printed package on a single line
+                let json_data: Value = serde_json::from_slice(data).map_err(io::Error::from)?;
+                let mut record = serde_json::to_vec(&json_data).map_err(io::Error::from)?;
+                record.push(b'\n');
+                self.append(&record)
+            },
+            FileRawDataWriteMode::Binary => {
+                let size = u32::try_from(data.len())
+                    .map_err(|_| FramingError::PackageTooLarge { size: data.len(), max_size: u32::MAX as usize })?;
+                let mut record = size.to_le_bytes().to_vec();
+                record.extend_from_slice(data);
+                self.append(&record)
+            },
+            FileRawDataWriteMode::Base64Lines => {
+                let mut record = BASE64.encode(data);
+                record.push('\n');
+                self.append(record.as_bytes())
+            },
+        }
     }
 }
 
@@ -49,4 +107,33 @@ mod tests {
         let obj = arr[0].as_object().expect("Expected a JSON object");
         assert_eq!(obj.get("key").unwrap(), "value");
     }
+
+    #[test]
+    fn journal_modes() {
+        // GIVEN
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let json_lines_path = tmp_dir.path().join("test.jsonl");
+        let binary_path = tmp_dir.path().join("test.bin");
+        let base64_path = tmp_dir.path().join("test.b64");
+        let test_json_packages = [b"{\n  \"id\": 1\n}".to_vec(), b"[2]".to_vec()];
+        let test_binary_packages = [vec![0x08, 0x01, 0xff], vec![]];
+
+        // WHEN
+        let mut json_lines_writer = FileRawDataWriter::with_mode(json_lines_path.to_str().unwrap(), FileRawDataWriteMode::JsonLines);
+        let mut binary_writer = FileRawDataWriter::with_mode(binary_path.to_str().unwrap(), FileRawDataWriteMode::Binary);
+        let mut base64_writer = FileRawDataWriter::with_mode(base64_path.to_str().unwrap(), FileRawDataWriteMode::Base64Lines);
+
+        // THEN
+        for test_package in &test_json_packages {
+            json_lines_writer.write_data(test_package).unwrap();
+        }
+        for test_package in &test_binary_packages {
+            binary_writer.write_data(test_package).unwrap();
+            base64_writer.write_data(test_package).unwrap();
+        }
+        assert!(json_lines_writer.write_data(&vec![0x08]).is_err());
+        assert_eq!("{\"id\":1}\n[2]\n", std::fs::read_to_string(json_lines_path).unwrap());
+        assert_eq!(vec![3, 0, 0, 0, 0x08, 0x01, 0xff, 0, 0, 0, 0], std::fs::read(binary_path).unwrap());
+        assert_eq!("CAH/\n\n", std::fs::read_to_string(base64_path).unwrap());
+    }
 }
@@ -0,0 +1,64 @@
+use ipc::FramingError;
+
+const DATA_KIND: u8 = 0x01;
+const ACK_KIND: u8 = 0x02;
+const NACK_KIND: u8 = 0x03;
+
+/// Link-level frame: kind byte, sequence number byte, then the payload (data frames only).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Frame {
+    Data { seq: u8, payload: Vec<u8> },
+    Ack { seq: u8 },
+    Nack { seq: u8 },
+}
+
+impl Frame {
+    pub fn encode(&self) -> Vec<u8> {
+        match self {
+            Self::Data { seq, payload } => {
+                let mut encoded = vec![DATA_KIND, *seq];
+                encoded.extend_from_slice(payload);
+                encoded
+            },
+            Self::Ack { seq } => vec![ACK_KIND, *seq],
+            Self::Nack { seq } => vec![NACK_KIND, *seq],
+        }
+    }
+
+    pub fn decode(encoded: &[u8]) -> Result<Self, FramingError> {
+        let [kind, seq, payload @ ..] = encoded else {
+            return Err(FramingError::Malformed(format!("frame is too short ({} bytes)", encoded.len())));
+        };
+        match (*kind, payload.is_empty()) {
+            (DATA_KIND, _) => Ok(Self::Data { seq: *seq, payload: payload.to_vec() }),
+            (ACK_KIND, true) => Ok(Self::Ack { seq: *seq }),
+            (NACK_KIND, true) => Ok(Self::Nack { seq: *seq }),
+            (ACK_KIND | NACK_KIND, false) => Err(FramingError::Malformed(format!("control frame 0x{kind:02X} carries a payload"))),
+            (any_other, _) => Err(FramingError::Malformed(format!("unknown frame kind 0x{any_other:02X}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode_sanity() {
+        // GIVEN
+        let test_frames = [
+            Frame::Data { seq: 0, payload: vec![] },
+            Frame::Data { seq: 255, payload: vec![0x01, 0x02, 0x03] },
+            Frame::Ack { seq: 7 },
+            Frame::Nack { seq: 8 },
+        ];
+
+        // THEN
+        for test_frame in &test_frames {
+            assert_eq!(test_frame, &Frame::decode(&test_frame.encode()).unwrap());
+        }
+        assert!(Frame::decode(&[DATA_KIND]).is_err());
+        assert!(Frame::decode(&[ACK_KIND, 0x00, 0x01]).is_err());
+        assert!(Frame::decode(&[0x7F, 0x00]).is_err());
+    }
+}
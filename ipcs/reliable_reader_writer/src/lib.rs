@@ -0,0 +1,379 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+pub use ipc::{FramingError, IpcError, IpcReader, IpcWriter};
+
+use crate::frame::Frame;
+
+pub type RawFrameReader = dyn IpcReader<Vec<u8>, IpcError> + Send;
+pub type RawFrameWriter = dyn IpcWriter<Vec<u8>, IpcError> + Send;
+
+#[derive(Clone, Debug)]
+pub struct ReliableChannelConfig {
+    /// Time to wait for an ACK before sending a data frame again.
+    /// The wrapped reader should time out sooner, otherwise its own timeout takes over.
+    pub retransmit_timeout: Duration,
+    pub max_retransmissions: u32,
+}
+
+/// Stop-and-wait delivery on top of a frame reader/writer pair, shared by `ReliableReader` and `ReliableWriter`.
+#[derive(Clone)]
+pub struct ReliableChannel {
+    link: Arc<Mutex<Link>>,
+}
+
+impl ReliableChannel {
+    pub fn new(raw_reader: Box<RawFrameReader>, raw_writer: Box<RawFrameWriter>, config: ReliableChannelConfig) -> Self {
+        Self {
+            link: Arc::new(Mutex::new(Link {
+                raw_reader,
+                raw_writer,
+                config,
+                next_tx_seq: 0,
+                last_rx_seq: None,
+                received: VecDeque::new(),
+                retransmitted_frames: 0,
+                duplicate_frames: 0,
+            })),
+        }
+    }
+
+    /// Total number of data frames sent again after a NACK or a missing ACK.
+    pub fn retransmitted_frames(&self) -> usize {
+        self.link.lock().map_or(0, |link| link.retransmitted_frames)
+    }
+
+    /// Total number of received data frames dropped as repetitions of the previous one.
+    pub fn duplicate_frames(&self) -> usize {
+        self.link.lock().map_or(0, |link| link.duplicate_frames)
+    }
+
+    fn get_mut(&mut self) -> Result<MutexGuard<'_, Link>, IpcError> {
+        self.link.lock().map_err(|err| IpcError::Unavailable(err.to_string()))
+    }
+}
+
+struct Link {
+    raw_reader: Box<RawFrameReader>,
+    raw_writer: Box<RawFrameWriter>,
+    config: ReliableChannelConfig,
+    next_tx_seq: u8,
+    last_rx_seq: Option<u8>,
+    received: VecDeque<Vec<u8>>,
+    retransmitted_frames: usize,
+    duplicate_frames: usize,
+}
+
+impl Link {
+    fn send(&mut self, payload: &[u8]) -> Result<(), IpcError> {
+        let seq = self.next_tx_seq;
+        let frame = Frame::Data { seq, payload: payload.to_vec() }.encode();
+        for attempt in 0..=self.config.max_retransmissions {
+            if attempt > 0 {
+                self.retransmitted_frames += 1;
+            }
+            self.raw_writer.write_data(&frame)?;
+            if self.wait_for_ack(seq)? {
+                self.next_tx_seq = seq.wrapping_add(1);
+                return Ok(());
+            }
+        }
+        Err(IpcError::Timeout)
+    }
+
+    /// Returns `false` once the frame `seq` has to be sent again.
+    fn wait_for_ack(&mut self, seq: u8) -> Result<bool, IpcError> {
+        let deadline = Instant::now() + self.config.retransmit_timeout;
+        while Instant::now() < deadline {
+            let raw_frame = match self.raw_reader.read_data() {
+                Ok(raw_frame) => raw_frame,
+                Err(IpcError::Timeout) => continue,
+                Err(IpcError::Framing(_)) => return Ok(false),
+                Err(err) => return Err(err),
+            };
+            match Frame::decode(&raw_frame) {
+                Ok(Frame::Ack { seq: acked_seq }) if acked_seq == seq => return Ok(true),
+                // A late ACK of an earlier frame
+                Ok(Frame::Ack { .. }) => (),
+                Ok(Frame::Nack { seq: nacked_seq }) if nacked_seq == seq => return Ok(false),
+                // A late NACK of an earlier frame, which has been delivered since
+                Ok(Frame::Nack { .. }) => (),
+                Ok(Frame::Data { seq, payload }) => self.accept_data(seq, payload)?,
+                Err(_) => return Ok(false),
+            }
+        }
+        Ok(false)
+    }
+
+    fn receive(&mut self) -> Result<Vec<u8>, IpcError> {
+        loop {
+            if let Some(payload) = self.received.pop_front() {
+                return Ok(payload);
+            }
+            let raw_frame = match self.raw_reader.read_data() {
+                Ok(raw_frame) => raw_frame,
+                Err(IpcError::Framing(_)) => {
+                    self.reject_data()?;
+                    continue;
+                },
+                Err(err) => return Err(err),
+            };
+            match Frame::decode(&raw_frame) {
+                Ok(Frame::Data { seq, payload }) => self.accept_data(seq, payload)?,
+                // Nothing is waiting for an ACK outside of `send`
+                Ok(Frame::Ack { .. } | Frame::Nack { .. }) => (),
+                Err(_) => self.reject_data()?,
+            }
+        }
+    }
+
+    fn accept_data(&mut self, seq: u8, payload: Vec<u8>) -> Result<(), IpcError> {
+        self.raw_writer.write_data(&Frame::Ack { seq }.encode())?;
+        // The sender missed our ACK and repeated the frame
+        if self.last_rx_seq == Some(seq) {
+            self.duplicate_frames += 1;
+            return Ok(());
+        }
+        self.last_rx_seq = Some(seq);
+        self.received.push_back(payload);
+        Ok(())
+    }
+
+    fn reject_data(&mut self) -> Result<(), IpcError> {
+        let expected_seq = self.last_rx_seq.map_or(0, |seq| seq.wrapping_add(1));
+        self.raw_writer.write_data(&Frame::Nack { seq: expected_seq }.encode())
+    }
+}
+
+pub struct ReliableReader {
+    channel: ReliableChannel,
+}
+
+impl ReliableReader {
+    pub fn new(channel: &ReliableChannel) -> Self {
+        Self { channel: channel.clone() }
+    }
+}
+
+impl IpcReader<Vec<u8>, IpcError> for ReliableReader {
+    fn read_data(&mut self) -> Result<Vec<u8>, IpcError> {
+        self.channel.get_mut()?.receive()
+    }
+}
+
+pub struct ReliableWriter {
+    channel: ReliableChannel,
+}
+
+impl ReliableWriter {
+    pub fn new(channel: &ReliableChannel) -> Self {
+        Self { channel: channel.clone() }
+    }
+}
+
+impl IpcWriter<Vec<u8>, IpcError> for ReliableWriter {
+    fn write_data(&mut self, data: &Vec<u8>) -> Result<(), IpcError> {
+        self.channel.get_mut()?.send(data)
+    }
+}
+
+mod frame;
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    const TEST_CONFIG: ReliableChannelConfig = ReliableChannelConfig {
+        retransmit_timeout: Duration::from_millis(100),
+        max_retransmissions: 3,
+    };
+
+    #[test]
+    fn sanity() {
+        // GIVEN
+        let test_packages = [vec![0x01], vec![0x02, 0x03], vec![]];
+        let ((a_reader, a_writer), (b_reader, b_writer)) = generate_link(0, 0);
+        let peer = thread::spawn(move || run_echo_peer(b_reader, b_writer, 3));
+
+        // WHEN
+        let channel = ReliableChannel::new(a_reader, a_writer, TEST_CONFIG);
+        let mut reader = ReliableReader::new(&channel);
+        let mut writer = ReliableWriter::new(&channel);
+
+        // THEN
+        for test_package in &test_packages {
+            writer.write_data(test_package).unwrap();
+            assert_eq!(test_package, &read_data_blocking(&mut reader));
+        }
+        assert_eq!(test_packages.to_vec(), peer.join().unwrap());
+        assert_eq!(0, channel.retransmitted_frames());
+    }
+
+    #[test]
+    fn retransmit_after_lost_ack() {
+        // GIVEN
+        let test_packages = [vec![0x01], vec![0x02]];
+        let ((a_reader, a_writer), (b_reader, b_writer)) = generate_link(0, 1);
+        let peer = thread::spawn(move || run_echo_peer(b_reader, b_writer, 2));
+
+        // WHEN
+        let channel = ReliableChannel::new(a_reader, a_writer, TEST_CONFIG);
+        let mut reader = ReliableReader::new(&channel);
+        let mut writer = ReliableWriter::new(&channel);
+
+        // THEN
+        for test_package in &test_packages {
+            writer.write_data(test_package).unwrap();
+            assert_eq!(test_package, &read_data_blocking(&mut reader));
+        }
+        assert_eq!(test_packages.to_vec(), peer.join().unwrap());
+        assert_eq!(1, channel.retransmitted_frames());
+    }
+
+    #[test]
+    fn retransmissions_exhausted() {
+        // GIVEN
+        let ((a_reader, a_writer), (_b_reader, _b_writer)) = generate_link(usize::MAX, 0);
+
+        // WHEN
+        let channel = ReliableChannel::new(a_reader, a_writer, TEST_CONFIG);
+        let mut writer = ReliableWriter::new(&channel);
+
+        // THEN
+        assert!(matches!(writer.write_data(&vec![0x01]), Err(IpcError::Timeout)));
+        assert_eq!(TEST_CONFIG.max_retransmissions as usize, channel.retransmitted_frames());
+    }
+
+    #[test]
+    fn retransmit_after_nack() {
+        // GIVEN
+        let test_package = vec![0x01];
+        let a_to_b = Arc::new(Mutex::new(VecDeque::new()));
+        let b_to_a = Arc::new(Mutex::new(VecDeque::from([
+            Frame::Nack { seq: 7 }.encode(),
+            Frame::Nack { seq: 0 }.encode(),
+            Frame::Ack { seq: 0 }.encode(),
+        ])));
+
+        // WHEN
+        let channel = ReliableChannel::new(
+            Box::new(QueueReader { queue: b_to_a }),
+            Box::new(QueueWriter { queue: a_to_b.clone(), drops_left: 0 }),
+            TEST_CONFIG,
+        );
+        let mut writer = ReliableWriter::new(&channel);
+
+        // THEN
+        writer.write_data(&test_package).unwrap();
+        // Only the NACK of the frame in flight made it go again
+        assert_eq!(1, channel.retransmitted_frames());
+        let sent_frame = Frame::Data { seq: 0, payload: test_package }.encode();
+        assert_eq!(VecDeque::from([sent_frame.clone(), sent_frame]), *a_to_b.lock().unwrap());
+    }
+
+    #[test]
+    fn duplicate_frame_dropped() {
+        // GIVEN
+        let test_packages = [vec![0x01], vec![0x02]];
+        let a_to_b = Arc::new(Mutex::new(VecDeque::new()));
+        let b_to_a = Arc::new(Mutex::new(VecDeque::from([
+            Frame::Data { seq: 0, payload: test_packages[0].clone() }.encode(),
+            Frame::Data { seq: 0, payload: test_packages[0].clone() }.encode(),
+            Frame::Data { seq: 1, payload: test_packages[1].clone() }.encode(),
+        ])));
+
+        // WHEN
+        let channel = ReliableChannel::new(
+            Box::new(QueueReader { queue: b_to_a }),
+            Box::new(QueueWriter { queue: a_to_b.clone(), drops_left: 0 }),
+            TEST_CONFIG,
+        );
+        let mut reader = ReliableReader::new(&channel);
+
+        // THEN
+        for test_package in &test_packages {
+            assert_eq!(test_package, &read_data_blocking(&mut reader));
+        }
+        assert_eq!(1, channel.duplicate_frames());
+        // The duplicate is acknowledged again, its sender missed the first ACK
+        let acks = [0, 0, 1].map(|seq| Frame::Ack { seq }.encode());
+        assert_eq!(VecDeque::from(acks), *a_to_b.lock().unwrap());
+    }
+
+    /// Echoes `packages_number` packages back and returns them, the peer channel must drop the duplicates.
+    fn run_echo_peer(raw_reader: Box<RawFrameReader>, raw_writer: Box<RawFrameWriter>, packages_number: usize) -> Vec<Vec<u8>> {
+        let channel = ReliableChannel::new(raw_reader, raw_writer, TEST_CONFIG);
+        let mut reader = ReliableReader::new(&channel);
+        let mut writer = ReliableWriter::new(&channel);
+        let mut received = vec![];
+        for _ in 0..packages_number {
+            let package = read_data_blocking(&mut reader);
+            writer.write_data(&package).unwrap();
+            received.push(package);
+        }
+        received
+    }
+
+    fn read_data_blocking(reader: &mut ReliableReader) -> Vec<u8> {
+        loop {
+            match reader.read_data() {
+                Ok(data) => return data,
+                Err(IpcError::Timeout) => continue,
+                Err(err) => panic!("unexpected read failure: {err}"),
+            }
+        }
+    }
+
+    type RawFramePair = (Box<RawFrameReader>, Box<RawFrameWriter>);
+
+    /// Two connected endpoints, the first `a_to_b_drops`/`b_to_a_drops` frames sent in each direction get lost.
+    fn generate_link(a_to_b_drops: usize, b_to_a_drops: usize) -> (RawFramePair, RawFramePair) {
+        let a_to_b = Arc::new(Mutex::new(VecDeque::new()));
+        let b_to_a = Arc::new(Mutex::new(VecDeque::new()));
+        (
+            (
+                Box::new(QueueReader { queue: b_to_a.clone() }),
+                Box::new(QueueWriter { queue: a_to_b.clone(), drops_left: a_to_b_drops }),
+            ),
+            (
+                Box::new(QueueReader { queue: a_to_b }),
+                Box::new(QueueWriter { queue: b_to_a, drops_left: b_to_a_drops }),
+            ),
+        )
+    }
+
+    struct QueueReader {
+        queue: Arc<Mutex<VecDeque<Vec<u8>>>>,
+    }
+
+    impl IpcReader<Vec<u8>, IpcError> for QueueReader {
+        fn read_data(&mut self) -> Result<Vec<u8>, IpcError> {
+            for _ in 0..10 {
+                if let Some(frame) = self.queue.lock().unwrap().pop_front() {
+                    return Ok(frame);
+                }
+                thread::sleep(Duration::from_millis(2));
+            }
+            Err(IpcError::Timeout)
+        }
+    }
+
+    struct QueueWriter {
+        queue: Arc<Mutex<VecDeque<Vec<u8>>>>,
+        drops_left: usize,
+    }
+
+    impl IpcWriter<Vec<u8>, IpcError> for QueueWriter {
+        fn write_data(&mut self, data: &Vec<u8>) -> Result<(), IpcError> {
+            if self.drops_left > 0 {
+                self.drops_left -= 1;
+                return Ok(());
+            }
+            self.queue.lock().unwrap().push_back(data.clone());
+            Ok(())
+        }
+    }
+}
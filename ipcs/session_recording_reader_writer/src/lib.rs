@@ -0,0 +1,357 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+pub use ipc::{FramingError, IpcError, IpcReader, IpcWriter};
+pub use session::{load_session, ReadErrorKind, SessionEvent, SessionEventKind};
+
+pub type RawFrameReader = dyn IpcReader<Vec<u8>, IpcError> + Send;
+pub type RawFrameWriter = dyn IpcWriter<Vec<u8>, IpcError> + Send;
+
+/// Shared session file sink, offsets are relative to the recorder creation.
+#[derive(Clone)]
+pub struct SessionRecorder {
+    sink: Arc<Mutex<Box<dyn Write + Send>>>,
+    start: Instant,
+}
+
+impl SessionRecorder {
+    pub fn new(sink: Box<dyn Write + Send>) -> Self {
+        Self {
+            sink: Arc::new(Mutex::new(sink)),
+            start: Instant::now(),
+        }
+    }
+
+    pub fn create(file_path: &Path) -> Result<Self, IpcError> {
+        Ok(Self::new(Box::new(File::create(file_path)?)))
+    }
+
+    fn record(&self, kind: SessionEventKind) {
+        let event = SessionEvent {
+            offset_us: self.start.elapsed().as_micros() as u64,
+            kind,
+        };
+        // Recording must never break the session it captures
+        let (Ok(line), Ok(mut sink)) = (event.to_line(), self.sink.lock()) else {
+            return;
+        };
+        let _ = sink.write_all(line.as_bytes());
+        let _ = sink.flush();
+    }
+}
+
+/// Records every frame read through `inner`, read timeouts and errors included.
+pub struct RecordingReader {
+    inner: Box<RawFrameReader>,
+    recorder: SessionRecorder,
+}
+
+impl RecordingReader {
+    pub fn new(inner: Box<RawFrameReader>, recorder: &SessionRecorder) -> Self {
+        Self { inner, recorder: recorder.clone() }
+    }
+}
+
+impl IpcReader<Vec<u8>, IpcError> for RecordingReader {
+    fn read_data(&mut self) -> Result<Vec<u8>, IpcError> {
+        let result = self.inner.read_data();
+        match &result {
+            Ok(frame) => self.recorder.record(SessionEventKind::Rx { frame: frame.clone() }),
+            Err(err) => self.recorder.record(SessionEventKind::rx_error(err)),
+        }
+        result
+    }
+}
+
+pub struct RecordingWriter {
+    inner: Box<RawFrameWriter>,
+    recorder: SessionRecorder,
+}
+
+impl RecordingWriter {
+    pub fn new(inner: Box<RawFrameWriter>, recorder: &SessionRecorder) -> Self {
+        Self { inner, recorder: recorder.clone() }
+    }
+}
+
+impl IpcWriter<Vec<u8>, IpcError> for RecordingWriter {
+    fn write_data(&mut self, data: &Vec<u8>) -> Result<(), IpcError> {
+        self.recorder.record(SessionEventKind::Tx { frame: data.clone() });
+        self.inner.write_data(data)
+    }
+}
+
+/// Plays a recorded session back, shared by `ReplayReader` and `ReplayWriter`.
+/// Written frames must match the recorded ones in order; with `paced` set, frames are read no sooner than they were recorded.
+#[derive(Clone)]
+pub struct ReplaySession {
+    replay: Arc<Mutex<Replay>>,
+}
+
+impl ReplaySession {
+    pub fn new(events: Vec<SessionEvent>, paced: bool) -> Self {
+        Self {
+            replay: Arc::new(Mutex::new(Replay {
+                events: events.into(),
+                played_events: 0,
+                paced,
+                start: Instant::now(),
+            })),
+        }
+    }
+
+    pub fn load(file_path: &Path, paced: bool) -> Result<Self, IpcError> {
+        Ok(Self::new(load_session(file_path)?, paced))
+    }
+
+    /// Number of recorded events not played yet, zero once the session has been reproduced entirely.
+    pub fn remaining_events(&self) -> usize {
+        self.replay.lock().map_or(0, |replay| replay.events.len())
+    }
+
+    fn get_mut(&mut self) -> Result<MutexGuard<'_, Replay>, IpcError> {
+        self.replay.lock().map_err(|err| IpcError::Unavailable(err.to_string()))
+    }
+}
+
+struct Replay {
+    events: VecDeque<SessionEvent>,
+    played_events: usize,
+    paced: bool,
+    start: Instant,
+}
+
+impl Replay {
+    fn read(&mut self) -> Result<Vec<u8>, IpcError> {
+        let Some(event) = self.events.front() else {
+            return Err(session_over());
+        };
+        let frame = match &event.kind {
+            // The recorded peer was waiting for a request at this point
+            SessionEventKind::Tx { .. } => return Err(IpcError::Timeout),
+            SessionEventKind::Rx { frame } => Ok(frame.clone()),
+            SessionEventKind::RxTimeout => Err(IpcError::Timeout),
+            SessionEventKind::RxError { error_kind, message } => Err(error_kind.to_error(message)),
+        };
+        self.wait_for(event.offset_us);
+        self.pop_event();
+        frame
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), IpcError> {
+        let Some(event) = self.events.front() else {
+            return Err(session_over());
+        };
+        match &event.kind {
+            SessionEventKind::Tx { frame } if frame == data => {
+                self.pop_event();
+                Ok(())
+            },
+            SessionEventKind::Tx { .. } => Err(self.mismatch("the written frame differs from the recorded one")),
+            _ => Err(self.mismatch("a frame was written where the recording reads one")),
+        }
+    }
+
+    fn wait_for(&self, offset_us: u64) {
+        if !self.paced {
+            return;
+        }
+        let due = self.start + Duration::from_micros(offset_us);
+        if let Some(delay) = due.checked_duration_since(Instant::now()) {
+            std::thread::sleep(delay);
+        }
+    }
+
+    fn pop_event(&mut self) {
+        self.events.pop_front();
+        self.played_events += 1;
+    }
+
+    fn mismatch(&self, reason: &str) -> IpcError {
+        IpcError::Io(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("session diverged at event #{}: {reason}", self.played_events),
+        ))
+    }
+}
+
+fn session_over() -> IpcError {
+    IpcError::Unavailable("the recorded session is over".into())
+}
+
+pub struct ReplayReader {
+    session: ReplaySession,
+}
+
+impl ReplayReader {
+    pub fn new(session: &ReplaySession) -> Self {
+        Self { session: session.clone() }
+    }
+}
+
+impl IpcReader<Vec<u8>, IpcError> for ReplayReader {
+    fn read_data(&mut self) -> Result<Vec<u8>, IpcError> {
+        self.session.get_mut()?.read()
+    }
+}
+
+pub struct ReplayWriter {
+    session: ReplaySession,
+}
+
+impl ReplayWriter {
+    pub fn new(session: &ReplaySession) -> Self {
+        Self { session: session.clone() }
+    }
+}
+
+impl IpcWriter<Vec<u8>, IpcError> for ReplayWriter {
+    fn write_data(&mut self, data: &Vec<u8>) -> Result<(), IpcError> {
+        self.session.get_mut()?.write(data)
+    }
+}
+
+mod session;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanity() {
+        // GIVEN
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let test_file_path = tmp_dir.path().join("session.jsonl");
+        let test_request = vec![0x01, 0x02];
+        let test_response = vec![0x03];
+
+        // WHEN
+        let recorder = SessionRecorder::create(&test_file_path).unwrap();
+        let mut recording_writer = RecordingWriter::new(Box::new(FakeWriter), &recorder);
+        let mut recording_reader = RecordingReader::new(
+            Box::new(FakeReader { responses: VecDeque::from([Err(IpcError::Timeout), Ok(test_response.clone())]) }),
+            &recorder,
+        );
+        recording_writer.write_data(&test_request).unwrap();
+        assert!(matches!(recording_reader.read_data(), Err(IpcError::Timeout)));
+        assert_eq!(test_response, recording_reader.read_data().unwrap());
+
+        // THEN
+        let session = ReplaySession::load(&test_file_path, true).unwrap();
+        assert_eq!(3, session.remaining_events());
+        let mut replay_reader = ReplayReader::new(&session);
+        let mut replay_writer = ReplayWriter::new(&session);
+        assert!(matches!(replay_reader.read_data(), Err(IpcError::Timeout)));
+        replay_writer.write_data(&test_request).unwrap();
+        assert!(matches!(replay_reader.read_data(), Err(IpcError::Timeout)));
+        assert_eq!(test_response, replay_reader.read_data().unwrap());
+        assert_eq!(0, session.remaining_events());
+        assert!(matches!(replay_reader.read_data(), Err(IpcError::Unavailable(_))));
+    }
+
+    #[test]
+    fn diverged_request() {
+        // GIVEN
+        let test_events = vec![
+            SessionEvent { offset_us: 0, kind: SessionEventKind::Tx { frame: vec![0x01] } },
+            SessionEvent { offset_us: 10, kind: SessionEventKind::Rx { frame: vec![0x02] } },
+        ];
+
+        // WHEN
+        let session = ReplaySession::new(test_events, false);
+        let mut replay_writer = ReplayWriter::new(&session);
+
+        // THEN
+        let IpcError::Io(err) = replay_writer.write_data(&vec![0x07]).unwrap_err() else {
+            panic!("expected an IO error");
+        };
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        assert!(err.to_string().contains("event #0"));
+        assert_eq!(2, session.remaining_events());
+    }
+
+    #[test]
+    fn replayed_read_errors() {
+        // GIVEN
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let test_file_path = tmp_dir.path().join("session.jsonl");
+        let test_response = vec![0x03];
+
+        // WHEN
+        let recorder = SessionRecorder::create(&test_file_path).unwrap();
+        let mut recording_reader = RecordingReader::new(
+            Box::new(FakeReader {
+                responses: VecDeque::from([
+                    Err(FramingError::InvalidSize("garbled".into()).into()),
+                    Err(IpcError::Reconnected),
+                    Err(IpcError::Io(io::Error::other("device gone"))),
+                    Ok(test_response.clone()),
+                ]),
+            }),
+            &recorder,
+        );
+        for _ in 0..4 {
+            let _ = recording_reader.read_data();
+        }
+
+        // THEN
+        let session = ReplaySession::load(&test_file_path, false).unwrap();
+        let mut replay_reader = ReplayReader::new(&session);
+        assert!(matches!(replay_reader.read_data(), Err(IpcError::Framing(FramingError::Malformed(message))) if message.contains("garbled")));
+        assert!(matches!(replay_reader.read_data(), Err(IpcError::Reconnected)));
+        assert!(matches!(replay_reader.read_data(), Err(IpcError::Io(err)) if err.to_string() == "device gone"));
+        assert_eq!(test_response, replay_reader.read_data().unwrap());
+    }
+
+    #[test]
+    fn replayed_exchange() {
+        // GIVEN
+        let test_request = b"{\"request_id\":0}".to_vec();
+        let test_response = b"{\"request_id\":0,\"status\":\"SUCCESS\"}".to_vec();
+        let test_events = vec![
+            SessionEvent { offset_us: 0, kind: SessionEventKind::Tx { frame: test_request.clone() } },
+            SessionEvent { offset_us: 1500, kind: SessionEventKind::RxTimeout },
+            SessionEvent { offset_us: 3000, kind: SessionEventKind::Rx { frame: test_response } },
+        ];
+
+        // WHEN
+        let session = ReplaySession::new(test_events, false);
+        let mut replay_reader = ReplayReader::new(&session);
+        let mut replay_writer = ReplayWriter::new(&session);
+
+        // THEN
+        assert!(matches!(exchange(&mut replay_writer, &mut replay_reader, &test_request), Err(IpcError::Timeout)));
+        assert_eq!(1, session.remaining_events());
+        // The recorded client gave up after the timeout, a request sent instead diverges from the session
+        let next_request = b"{\"request_id\":1}".to_vec();
+        assert!(matches!(exchange(&mut replay_writer, &mut replay_reader, &next_request), Err(IpcError::Io(_))));
+    }
+
+    /// Stands in for a client transaction: writes `request` and reads a single response.
+    fn exchange(writer: &mut ReplayWriter, reader: &mut ReplayReader, request: &Vec<u8>) -> Result<Vec<u8>, IpcError> {
+        writer.write_data(request)?;
+        reader.read_data()
+    }
+
+    struct FakeReader {
+        responses: VecDeque<Result<Vec<u8>, IpcError>>,
+    }
+
+    impl IpcReader<Vec<u8>, IpcError> for FakeReader {
+        fn read_data(&mut self) -> Result<Vec<u8>, IpcError> {
+            self.responses.pop_front().unwrap_or(Err(IpcError::Timeout))
+        }
+    }
+
+    struct FakeWriter;
+
+    impl IpcWriter<Vec<u8>, IpcError> for FakeWriter {
+        fn write_data(&mut self, _data: &Vec<u8>) -> Result<(), IpcError> {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use ipc::{FramingError, IpcError};
+use serde::{Deserialize, Serialize};
+
+/// A single line of a session file, `offset_us` is counted from the start of the recording.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionEvent {
+    pub offset_us: u64,
+    #[serde(flatten)]
+    pub kind: SessionEventKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SessionEventKind {
+    Tx { frame: Vec<u8> },
+    Rx { frame: Vec<u8> },
+    RxTimeout,
+    /// A failed read, the error is kept as its category and message.
+    RxError { error_kind: ReadErrorKind, message: String },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadErrorKind {
+    Io,
+    Framing,
+    Unavailable,
+    Reconnected,
+}
+
+impl SessionEventKind {
+    pub fn rx_error(err: &IpcError) -> Self {
+        let (error_kind, message) = match err {
+            IpcError::Timeout => return Self::RxTimeout,
+            IpcError::Io(err) => (ReadErrorKind::Io, err.to_string()),
+            IpcError::Framing(err) => (ReadErrorKind::Framing, err.to_string()),
+            IpcError::Unavailable(message) => (ReadErrorKind::Unavailable, message.clone()),
+            IpcError::Reconnected => (ReadErrorKind::Reconnected, String::new()),
+        };
+        Self::RxError { error_kind, message }
+    }
+}
+
+impl ReadErrorKind {
+    /// Rebuilds an error of the recorded category, framing errors all come back as malformed packages.
+    pub(crate) fn to_error(self, message: &str) -> IpcError {
+        match self {
+            Self::Io => IpcError::Io(io::Error::other(message)),
+            Self::Framing => FramingError::Malformed(message.to_string()).into(),
+            Self::Unavailable => IpcError::Unavailable(message.to_string()),
+            Self::Reconnected => IpcError::Reconnected,
+        }
+    }
+}
+
+impl SessionEvent {
+    pub(crate) fn to_line(&self) -> Result<String, IpcError> {
+        let mut line = serde_json::to_string(self).map_err(io::Error::from)?;
+        line.push('\n');
+        Ok(line)
+    }
+}
+
+/// Reads a session file written by `SessionRecorder`, one JSON event per line.
+pub fn load_session(file_path: &Path) -> Result<Vec<SessionEvent>, IpcError> {
+    let file = File::open(file_path)?;
+    let mut events = vec![];
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        events.push(serde_json::from_str(&line).map_err(io::Error::from)?);
+    }
+    Ok(events)
+}
@@ -0,0 +1,105 @@
+use ipc::FramingError;
+
+use crate::{BITS_IN_BYTE, SizeDecoder, max_encodable_size, validate_encoded_length};
+use super::SizeEncoder;
+
+pub struct BigEndianSizeEncoder {
+    encoded_length: usize,
+}
+
+impl BigEndianSizeEncoder {
+    pub fn new(encoded_length: usize) -> Result<Self, FramingError> {
+        validate_encoded_length(encoded_length)?;
+        Ok(Self { encoded_length })
+    }
+}
+
+impl SizeEncoder for BigEndianSizeEncoder {
+    fn encode(&self, size: usize) -> Result<Vec<u8>, FramingError> {
+        let max_size = max_encodable_size(self.encoded_length);
+        if size > max_size {
+            return Err(FramingError::PackageTooLarge { size, max_size });
+        }
+        let mut encoded_size = vec![0; self.encoded_length];
+        encoded_size.iter_mut().rev().enumerate().for_each(
+            |(i, c)| {
+                let less_significant_byte = ((size >> (BITS_IN_BYTE * i)) & 0xFF) as u8;
+                *c = less_significant_byte;
+            }
+        );
+        Ok(encoded_size)
+    }
+}
+
+pub struct BigEndianSizeDecoder {
+    encoded_length: usize,
+}
+
+impl BigEndianSizeDecoder {
+    pub fn new(encoded_length: usize) -> Result<Self, FramingError> {
+        validate_encoded_length(encoded_length)?;
+        Ok(Self { encoded_length })
+    }
+}
+
+impl SizeDecoder for BigEndianSizeDecoder {
+    fn missing_raw_data_size(&self, raw_data: &[u8]) -> Result<usize, FramingError> {
+        Ok(self.encoded_length.saturating_sub(raw_data.len()))
+    }
+
+    fn decode(&self, raw_data: &[u8]) -> Result<usize, FramingError> {
+        if raw_data.len() != self.encoded_length {
+            return Err(FramingError::InvalidSize(format!("received encoded data size ({}) has unexpected length (expected {})", raw_data.len(), self.encoded_length)));
+        }
+        let mut decoded_size: usize = 0;
+        for &byte in raw_data.iter() {
+            decoded_size <<= BITS_IN_BYTE;
+            decoded_size |= byte as usize;
+        }
+        Ok(decoded_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode_sanity() {
+        // GIVEN
+        let encoded_size_len = 4;
+        let test_sizes = [u32::MIN as usize, 10, 1234, 145670, u32::MAX as usize];
+
+        // WHEN
+        let encoder = BigEndianSizeEncoder::new(encoded_size_len).unwrap();
+        let decoder = BigEndianSizeDecoder::new(encoded_size_len).unwrap();
+
+        // THEN
+        assert_eq!(vec![0x00, 0x00, 0x04, 0xD2], encoder.encode(1234).unwrap());
+        test_sizes.iter().for_each(
+            |s| {
+                let encoded_size = encoder.encode(*s).unwrap();
+                assert_eq!(0, decoder.missing_raw_data_size(&encoded_size).unwrap());
+                let decoded_size = decoder.decode(&encoded_size).unwrap();
+                assert_eq!(*s, decoded_size);
+            }
+        );
+    }
+
+    #[test]
+    fn size_out_of_range() {
+        // GIVEN
+        let encoded_size_len = 2;
+
+        // WHEN
+        let encoder = BigEndianSizeEncoder::new(encoded_size_len).unwrap();
+
+        // THEN
+        assert_eq!(vec![0xFF, 0xFF], encoder.encode(0xFFFF).unwrap());
+        assert!(matches!(encoder.encode(0x10000), Err(FramingError::PackageTooLarge { size: 0x10000, max_size: 0xFFFF })));
+        assert_eq!(vec![0xFF; 8], BigEndianSizeEncoder::new(8).unwrap().encode(usize::MAX).unwrap());
+        assert!(matches!(BigEndianSizeEncoder::new(0), Err(FramingError::InvalidSize(_))));
+        assert!(matches!(BigEndianSizeEncoder::new(9), Err(FramingError::InvalidSize(_))));
+        assert!(matches!(BigEndianSizeDecoder::new(9), Err(FramingError::InvalidSize(_))));
+    }
+}
@@ -0,0 +1,78 @@
+use crc::{Crc, CRC_16_IBM_3740, CRC_32_ISO_HDLC};
+
+use crate::ChecksumCalculator;
+
+/// CRC-16/CCITT-FALSE, transmitted little-endian.
+pub struct Crc16Checksum {
+    crc: Crc<u16>,
+}
+
+impl Crc16Checksum {
+    pub fn new() -> Self {
+        Self { crc: Crc::<u16>::new(&CRC_16_IBM_3740) }
+    }
+}
+
+impl Default for Crc16Checksum {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChecksumCalculator for Crc16Checksum {
+    fn checksum_length(&self) -> usize {
+        std::mem::size_of::<u16>()
+    }
+
+    fn calculate(&self, data: &[u8]) -> Vec<u8> {
+        self.crc.checksum(data).to_le_bytes().to_vec()
+    }
+}
+
+/// CRC-32 (IEEE 802.3), transmitted little-endian.
+pub struct Crc32Checksum {
+    crc: Crc<u32>,
+}
+
+impl Crc32Checksum {
+    pub fn new() -> Self {
+        Self { crc: Crc::<u32>::new(&CRC_32_ISO_HDLC) }
+    }
+}
+
+impl Default for Crc32Checksum {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChecksumCalculator for Crc32Checksum {
+    fn checksum_length(&self) -> usize {
+        std::mem::size_of::<u32>()
+    }
+
+    fn calculate(&self, data: &[u8]) -> Vec<u8> {
+        self.crc.checksum(data).to_le_bytes().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_values_sanity() {
+        // GIVEN
+        let check_input = b"123456789";
+
+        // WHEN
+        let crc16 = Crc16Checksum::new();
+        let crc32 = Crc32Checksum::new();
+
+        // THEN
+        assert_eq!(crc16.checksum_length(), 2);
+        assert_eq!(crc16.calculate(check_input), 0x29B1u16.to_le_bytes().to_vec());
+        assert_eq!(crc32.checksum_length(), 4);
+        assert_eq!(crc32.calculate(check_input), 0xCBF43926u32.to_le_bytes().to_vec());
+    }
+}
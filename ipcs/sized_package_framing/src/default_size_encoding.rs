@@ -0,0 +1,103 @@
+use ipc::FramingError;
+
+use crate::{BITS_IN_BYTE, SizeDecoder, max_encodable_size, validate_encoded_length};
+use super::SizeEncoder;
+
+pub struct DefaultSizeEncoder {
+    encoded_length: usize,
+}
+
+impl DefaultSizeEncoder {
+    pub fn new(encoded_length: usize) -> Result<Self, FramingError> {
+        validate_encoded_length(encoded_length)?;
+        Ok(Self { encoded_length })
+    }
+}
+
+impl SizeEncoder for DefaultSizeEncoder {
+    fn encode(&self, size: usize) -> Result<Vec<u8>, FramingError> {
+        let max_size = max_encodable_size(self.encoded_length);
+        if size > max_size {
+            return Err(FramingError::PackageTooLarge { size, max_size });
+        }
+        let mut encoded_size = vec![0; self.encoded_length];
+        encoded_size.iter_mut().enumerate().for_each(
+            |(i, c)| {
+                let less_significant_byte = ((size >> (BITS_IN_BYTE * i)) & 0xFF) as u8;
+                *c = less_significant_byte;
+            }
+        );
+        Ok(encoded_size.to_vec())
+    }
+}
+
+pub struct DefaultSizeDecoder {
+    encoded_length: usize,
+}
+
+impl DefaultSizeDecoder {
+    pub fn new(encoded_length: usize) -> Result<Self, FramingError> {
+        validate_encoded_length(encoded_length)?;
+        Ok(Self { encoded_length })
+    }
+}
+
+impl SizeDecoder for DefaultSizeDecoder {
+    fn missing_raw_data_size(&self, raw_data: &[u8]) -> Result<usize, FramingError> {
+        Ok(self.encoded_length.saturating_sub(raw_data.len()))
+    }
+
+    fn decode(&self, raw_data: &[u8]) -> Result<usize, FramingError> {
+        if raw_data.len() != self.encoded_length {
+            return Err(FramingError::InvalidSize(format!("received encoded data size ({}) has unexpected length (expected {})", raw_data.len(), self.encoded_length)));
+        }
+        let mut decoded_size: usize = 0;
+        for &byte in raw_data.iter().rev() {
+            decoded_size <<= BITS_IN_BYTE;
+            decoded_size |= byte as usize;
+        }
+        Ok(decoded_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode_sanity() {
+        // GIVEN
+        let encoded_size_len = 4;
+        let test_sizes = [u32::MIN as usize, 10, 1234, 145670, u32::MAX as usize];
+
+        // WHEN
+        let encoder = DefaultSizeEncoder::new(encoded_size_len).unwrap();
+        let decoder = DefaultSizeDecoder::new(encoded_size_len).unwrap();
+
+        // THEN
+        test_sizes.iter().for_each(
+            |s| {
+                let encoded_size = encoder.encode(*s).unwrap();
+                let decoded_size = decoder.decode(&encoded_size).unwrap();
+                assert_eq!(*s, decoded_size);
+            }
+        );
+    }
+
+    #[test]
+    fn size_out_of_range() {
+        // GIVEN
+        let encoded_size_len = 1;
+
+        // WHEN
+        let encoder = DefaultSizeEncoder::new(encoded_size_len).unwrap();
+
+        // THEN
+        assert_eq!(vec![0xFF], encoder.encode(0xFF).unwrap());
+        assert!(matches!(encoder.encode(300), Err(FramingError::PackageTooLarge { size: 300, max_size: 0xFF })));
+        assert_eq!(vec![0xFF; 8], DefaultSizeEncoder::new(8).unwrap().encode(usize::MAX).unwrap());
+        assert!(matches!(DefaultSizeEncoder::new(0), Err(FramingError::InvalidSize(_))));
+        assert!(matches!(DefaultSizeEncoder::new(9), Err(FramingError::InvalidSize(_))));
+        assert!(matches!(DefaultSizeDecoder::new(9), Err(FramingError::InvalidSize(_))));
+    }
+}
@@ -0,0 +1,308 @@
+use std::io::Read;
+
+use ipc::{FramingError, IpcError};
+
+#[cfg(feature = "async")]
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+pub use big_endian_size_encoding::{BigEndianSizeDecoder, BigEndianSizeEncoder};
+pub use default_size_encoding::{DefaultSizeDecoder, DefaultSizeEncoder};
+pub use varint_size_encoding::{VarintSizeDecoder, VarintSizeEncoder};
+pub use crc_checksum::{Crc16Checksum, Crc32Checksum};
+
+/// Reads preamble + size + data (+ checksum) packages from a byte stream.
+pub struct SizedPackageDecoder {
+    preamble: Vec<u8>,
+    size_decoder: Box<dyn SizeDecoder + Send + Sync>,
+    checksum: Option<Box<dyn ChecksumCalculator + Send + Sync>>,
+    max_package_size: usize,
+    discarded_bytes: usize,
+}
+
+impl SizedPackageDecoder {
+    pub fn new(
+        preamble: &[u8],
+        size_decoder: Box<dyn SizeDecoder + Send + Sync>,
+        checksum: Option<Box<dyn ChecksumCalculator + Send + Sync>>,
+        max_package_size: usize,
+    ) -> Self {
+        Self {
+            preamble: preamble.to_vec(),
+            size_decoder,
+            checksum,
+            max_package_size,
+            discarded_bytes: 0,
+        }
+    }
+
+    /// Total number of bytes thrown away while searching for a preamble.
+    pub fn discarded_bytes(&self) -> usize {
+        self.discarded_bytes
+    }
+
+    pub fn read_package<R: Read + ?Sized>(&mut self, input: &mut R) -> Result<Vec<u8>, IpcError> {
+        let mut decoding = PackageDecoding::default();
+        loop {
+            let mut chunk = vec![0; self.missing_bytes(&decoding)?];
+            input.read_exact(chunk.as_mut_slice())?;
+            if let Some(package) = self.consume(&mut decoding, &chunk)? {
+                return Ok(package);
+            }
+        }
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn read_package_async<R: AsyncRead + Unpin + ?Sized>(&mut self, input: &mut R) -> Result<Vec<u8>, IpcError> {
+        let mut decoding = PackageDecoding::default();
+        loop {
+            let mut chunk = vec![0; self.missing_bytes(&decoding)?];
+            input.read_exact(chunk.as_mut_slice()).await?;
+            if let Some(package) = self.consume(&mut decoding, &chunk)? {
+                return Ok(package);
+            }
+        }
+    }
+
+    fn missing_bytes(&self, decoding: &PackageDecoding) -> Result<usize, FramingError> {
+        match decoding.stage {
+            DecodingStage::Preamble if decoding.preamble_window.is_empty() => Ok(self.preamble.len()),
+            DecodingStage::Preamble => Ok(1),
+            DecodingStage::Size => self.size_decoder.missing_raw_data_size(&decoding.size_buff),
+            DecodingStage::Data { package_size } => Ok(package_size),
+            DecodingStage::Checksum => Ok(self.checksum.as_ref().map_or(0, |checksum| checksum.checksum_length())),
+        }
+    }
+
+    /// Advances `decoding` with the bytes requested by `missing_bytes`, returns the package once it is complete.
+    fn consume(&mut self, decoding: &mut PackageDecoding, chunk: &[u8]) -> Result<Option<Vec<u8>>, FramingError> {
+        match decoding.stage {
+            DecodingStage::Preamble => {
+                if decoding.preamble_window.is_empty() {
+                    decoding.preamble_window = chunk.to_vec();
+                } else {
+                    decoding.preamble_window.remove(0);
+                    decoding.preamble_window.extend_from_slice(chunk);
+                    self.discarded_bytes += chunk.len();
+                }
+                if decoding.preamble_window == self.preamble {
+                    decoding.stage = DecodingStage::Size;
+                }
+                Ok(None)
+            },
+            DecodingStage::Size => {
+                decoding.size_buff.extend_from_slice(chunk);
+                if self.size_decoder.missing_raw_data_size(&decoding.size_buff)? != 0 {
+                    return Ok(None);
+                }
+                let package_size = self.size_decoder.decode(&decoding.size_buff)?;
+                if package_size > self.max_package_size {
+                    return Err(FramingError::PackageTooLarge { size: package_size, max_size: self.max_package_size });
+                }
+                decoding.stage = DecodingStage::Data { package_size };
+                Ok(None)
+            },
+            DecodingStage::Data { .. } => {
+                decoding.data_buff = chunk.to_vec();
+                if self.checksum.is_none() {
+                    return Ok(Some(std::mem::take(&mut decoding.data_buff)));
+                }
+                decoding.stage = DecodingStage::Checksum;
+                Ok(None)
+            },
+            DecodingStage::Checksum => {
+                if let Some(checksum) = &self.checksum {
+                    let mut checked_data = decoding.size_buff.clone();
+                    checked_data.extend_from_slice(&decoding.data_buff);
+                    let expected_checksum = checksum.calculate(&checked_data);
+                    if expected_checksum != chunk {
+                        return Err(FramingError::ChecksumMismatch { expected: expected_checksum, received: chunk.to_vec() });
+                    }
+                }
+                Ok(Some(std::mem::take(&mut decoding.data_buff)))
+            },
+        }
+    }
+}
+
+#[derive(Default)]
+struct PackageDecoding {
+    stage: DecodingStage,
+    preamble_window: Vec<u8>,
+    size_buff: Vec<u8>,
+    data_buff: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Default)]
+enum DecodingStage {
+    #[default]
+    Preamble,
+    Size,
+    Data { package_size: usize },
+    Checksum,
+}
+
+pub trait SizeDecoder {
+    /// Number of bytes to read after `raw_data` before the size can be decoded, 0 once it is complete.
+    fn missing_raw_data_size(&self, raw_data: &[u8]) -> Result<usize, FramingError>;
+    fn decode(&self, raw_data: &[u8]) -> Result<usize, FramingError>;
+}
+
+/// Builds preamble + size + data (+ checksum) packages.
+pub struct SizedPackageEncoder {
+    preamble: Vec<u8>,
+    size_encoder: Box<dyn SizeEncoder + Send + Sync>,
+    checksum: Option<Box<dyn ChecksumCalculator + Send + Sync>>,
+}
+
+impl SizedPackageEncoder {
+    pub fn new(
+        preamble: &[u8],
+        size_encoder: Box<dyn SizeEncoder + Send + Sync>,
+        checksum: Option<Box<dyn ChecksumCalculator + Send + Sync>>,
+    ) -> Self {
+        Self {
+            preamble: preamble.to_vec(),
+            size_encoder,
+            checksum,
+        }
+    }
+
+    pub fn encode_package(&self, data: &[u8]) -> Result<Vec<u8>, FramingError> {
+        let mut package_data = vec![];
+        package_data.extend_from_slice(&self.preamble);
+        let encoded_size = self.size_encoder.encode(data.len())?;
+        package_data.extend_from_slice(&encoded_size);
+        package_data.extend_from_slice(data);
+        if let Some(checksum) = &self.checksum {
+            let checksum = checksum.calculate(&package_data[self.preamble.len()..]);
+            package_data.extend_from_slice(&checksum);
+        }
+        Ok(package_data)
+    }
+}
+
+pub trait SizeEncoder {
+    fn encode(&self, size: usize) -> Result<Vec<u8>, FramingError>;
+}
+
+const BITS_IN_BYTE: usize = 8;
+
+/// The size has to fit into a `usize`, longer fields would lose the high bytes.
+pub(crate) fn validate_encoded_length(encoded_length: usize) -> Result<(), FramingError> {
+    if encoded_length == 0 || encoded_length > size_of::<usize>() {
+        return Err(FramingError::InvalidSize(format!(
+            "size field length ({encoded_length}) is out of the supported range (1 to {})",
+            size_of::<usize>()
+        )));
+    }
+    Ok(())
+}
+
+/// Largest size a fixed length size field can hold, `encoded_length` has to be valid.
+pub(crate) fn max_encodable_size(encoded_length: usize) -> usize {
+    usize::MAX >> (BITS_IN_BYTE * (size_of::<usize>() - encoded_length))
+}
+
+/// Calculates the package trailer, covering the encoded size and the package data.
+pub trait ChecksumCalculator {
+    fn checksum_length(&self) -> usize;
+    fn calculate(&self, data: &[u8]) -> Vec<u8>;
+}
+
+mod big_endian_size_encoding;
+mod crc_checksum;
+mod default_size_encoding;
+mod varint_size_encoding;
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn encode_decode_sanity() {
+        // GIVEN
+        let test_preamble = "MSG_PREAMBLE";
+        let test_packages = [vec![], vec![0x11; 3], vec![0x22; 300]];
+
+        // WHEN
+        let encoder = SizedPackageEncoder::new(
+            test_preamble.as_bytes(),
+            Box::new(DefaultSizeEncoder::new(4).unwrap()),
+            Some(Box::new(Crc16Checksum::new())),
+        );
+        let mut decoder = SizedPackageDecoder::new(
+            test_preamble.as_bytes(),
+            Box::new(DefaultSizeDecoder::new(4).unwrap()),
+            Some(Box::new(Crc16Checksum::new())),
+            1024,
+        );
+
+        // THEN
+        let mut stream = vec![];
+        for test_package in &test_packages {
+            stream.extend_from_slice(&encoder.encode_package(test_package).unwrap());
+        }
+        let mut input = Cursor::new(stream);
+        for test_package in &test_packages {
+            assert_eq!(test_package, &decoder.read_package(&mut input).unwrap());
+        }
+        assert!(decoder.read_package(&mut input).is_err());
+        assert_eq!(0, decoder.discarded_bytes());
+    }
+
+    #[test]
+    fn preamble_resync() {
+        // GIVEN
+        let test_preamble = "MSG_PREAMBLE";
+        let test_garbage = "\0MSG_PRE MSG_";
+        let test_package = vec![0x01, 0x02];
+
+        // WHEN
+        let encoder = SizedPackageEncoder::new(
+            test_preamble.as_bytes(),
+            Box::new(VarintSizeEncoder::new()),
+            None,
+        );
+        let mut decoder = SizedPackageDecoder::new(
+            test_preamble.as_bytes(),
+            Box::new(VarintSizeDecoder::new()),
+            None,
+            1024,
+        );
+
+        // THEN
+        let mut stream = test_garbage.as_bytes().to_vec();
+        stream.extend_from_slice(&encoder.encode_package(&test_package).unwrap());
+        let mut input = Cursor::new(stream);
+        assert_eq!(test_package, decoder.read_package(&mut input).unwrap());
+        assert_eq!(test_garbage.len(), decoder.discarded_bytes());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn read_package_async_sanity() {
+        // GIVEN
+        let test_preamble = "MSG_PREAMBLE";
+        let test_package = vec![0x01, 0x02];
+
+        // WHEN
+        let encoder = SizedPackageEncoder::new(
+            test_preamble.as_bytes(),
+            Box::new(DefaultSizeEncoder::new(4).unwrap()),
+            Some(Box::new(Crc32Checksum::new())),
+        );
+        let mut decoder = SizedPackageDecoder::new(
+            test_preamble.as_bytes(),
+            Box::new(DefaultSizeDecoder::new(4).unwrap()),
+            Some(Box::new(Crc32Checksum::new())),
+            1024,
+        );
+
+        // THEN
+        let stream = encoder.encode_package(&test_package).unwrap();
+        let mut input = stream.as_slice();
+        assert_eq!(test_package, decoder.read_package_async(&mut input).await.unwrap());
+    }
+}
@@ -0,0 +1,115 @@
+use ipc::FramingError;
+
+use crate::SizeDecoder;
+use super::SizeEncoder;
+
+const PAYLOAD_BITS: u32 = 7;
+const PAYLOAD_MASK: u8 = 0x7F;
+const CONTINUATION_FLAG: u8 = 0x80;
+const MAX_ENCODED_LENGTH: usize = usize::BITS.div_ceil(PAYLOAD_BITS) as usize;
+
+/// Unsigned LEB128 size encoding, as used for protobuf varints.
+#[derive(Default)]
+pub struct VarintSizeEncoder;
+
+impl VarintSizeEncoder {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl SizeEncoder for VarintSizeEncoder {
+    fn encode(&self, size: usize) -> Result<Vec<u8>, FramingError> {
+        let mut encoded_size = vec![];
+        let mut remaining_size = size;
+        loop {
+            let payload = (remaining_size as u8) & PAYLOAD_MASK;
+            remaining_size >>= PAYLOAD_BITS;
+            if remaining_size == 0 {
+                encoded_size.push(payload);
+                return Ok(encoded_size);
+            }
+            encoded_size.push(payload | CONTINUATION_FLAG);
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct VarintSizeDecoder;
+
+impl VarintSizeDecoder {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl SizeDecoder for VarintSizeDecoder {
+    fn missing_raw_data_size(&self, raw_data: &[u8]) -> Result<usize, FramingError> {
+        match raw_data.last() {
+            None => Ok(1),
+            Some(byte) if byte & CONTINUATION_FLAG == 0 => Ok(0),
+            Some(_) if raw_data.len() >= MAX_ENCODED_LENGTH => Err(FramingError::InvalidSize(format!("received varint size is longer than {MAX_ENCODED_LENGTH} bytes"))),
+            Some(_) => Ok(1),
+        }
+    }
+
+    fn decode(&self, raw_data: &[u8]) -> Result<usize, FramingError> {
+        if raw_data.is_empty() || raw_data.len() > MAX_ENCODED_LENGTH {
+            return Err(FramingError::InvalidSize(format!("received varint size has unexpected length ({})", raw_data.len())));
+        }
+        let mut decoded_size: usize = 0;
+        for (i, &byte) in raw_data.iter().enumerate() {
+            let is_last = i == raw_data.len() - 1;
+            if is_last == (byte & CONTINUATION_FLAG != 0) {
+                return Err(FramingError::InvalidSize(format!("received varint size has invalid continuation flag at byte {i}")));
+            }
+            let payload = ((byte & PAYLOAD_MASK) as usize)
+                .checked_shl(PAYLOAD_BITS * i as u32)
+                .filter(|shifted| shifted >> (PAYLOAD_BITS * i as u32) == (byte & PAYLOAD_MASK) as usize)
+                .ok_or(FramingError::InvalidSize("received varint size overflows usize".to_string()))?;
+            decoded_size |= payload;
+        }
+        Ok(decoded_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode_sanity() {
+        // GIVEN
+        let test_sizes = [0, 1, 127, 128, 300, 16384, u32::MAX as usize, usize::MAX];
+
+        // WHEN
+        let encoder = VarintSizeEncoder::new();
+        let decoder = VarintSizeDecoder::new();
+
+        // THEN
+        assert_eq!(vec![0xAC, 0x02], encoder.encode(300).unwrap());
+        test_sizes.iter().for_each(
+            |s| {
+                let encoded_size = encoder.encode(*s).unwrap();
+                for i in 0..encoded_size.len() {
+                    assert_eq!(1, decoder.missing_raw_data_size(&encoded_size[..i]).unwrap());
+                }
+                assert_eq!(0, decoder.missing_raw_data_size(&encoded_size).unwrap());
+                let decoded_size = decoder.decode(&encoded_size).unwrap();
+                assert_eq!(*s, decoded_size);
+            }
+        );
+    }
+
+    #[test]
+    fn decode_invalid_data() {
+        // GIVEN
+        let decoder = VarintSizeDecoder::new();
+
+        // THEN
+        assert!(decoder.missing_raw_data_size(&[0xFF; MAX_ENCODED_LENGTH]).is_err());
+        assert!(decoder.decode(&[0x80]).is_err());
+        assert!(decoder.decode(&[0x01, 0x01]).is_err());
+        assert!(decoder.decode(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F]).is_err());
+    }
+}
@@ -0,0 +1,188 @@
+use std::io::{Read, Write};
+
+pub use ipc::{IpcError, IpcReader, IpcWriter};
+pub use sized_package_framing::{ChecksumCalculator, SizeDecoder, SizeEncoder, SizedPackageDecoder, SizedPackageEncoder};
+pub use stream_port::{StreamConnector, StreamPort};
+
+pub struct StreamSizedPackageReader<Stream> {
+    port: StreamPort<Stream>,
+    package_decoder: SizedPackageDecoder,
+}
+
+impl<Stream> StreamSizedPackageReader<Stream> {
+    pub fn new(
+        port: &StreamPort<Stream>,
+        preamble: &[u8],
+        size_decoder: Box<dyn SizeDecoder + Send + Sync>,
+        checksum: Option<Box<dyn ChecksumCalculator + Send + Sync>>,
+        max_package_size: usize,
+    ) -> Self {
+        Self {
+            port: port.clone(),
+            package_decoder: SizedPackageDecoder::new(preamble, size_decoder, checksum, max_package_size),
+        }
+    }
+}
+
+impl<Stream: Read + Write> IpcReader<Vec<u8>, IpcError> for StreamSizedPackageReader<Stream> {
+    fn read_data(&mut self) -> Result<Vec<u8>, IpcError> {
+        self.port.with_stream(|stream| self.package_decoder.read_package(stream))
+    }
+}
+
+pub struct StreamSizedPackageWriter<Stream> {
+    port: StreamPort<Stream>,
+    package_encoder: SizedPackageEncoder,
+}
+
+impl<Stream> StreamSizedPackageWriter<Stream> {
+    pub fn new(
+        port: &StreamPort<Stream>,
+        preamble: &[u8],
+        size_encoder: Box<dyn SizeEncoder + Send + Sync>,
+        checksum: Option<Box<dyn ChecksumCalculator + Send + Sync>>,
+    ) -> Self {
+        Self {
+            port: port.clone(),
+            package_encoder: SizedPackageEncoder::new(preamble, size_encoder, checksum),
+        }
+    }
+}
+
+impl<Stream: Read + Write> IpcWriter<Vec<u8>, IpcError> for StreamSizedPackageWriter<Stream> {
+    fn write_data(&mut self, data: &Vec<u8>) -> Result<(), IpcError> {
+        let package_data = self.package_encoder.encode_package(data)?;
+        self.port.with_stream(|stream| Ok(stream.write_all(&package_data)?))
+    }
+}
+
+mod stream_port;
+
+#[cfg(test)]
+mod tests {
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    use sized_package_framing::{DefaultSizeDecoder, DefaultSizeEncoder};
+
+    use super::*;
+
+    const TEST_PREAMBLE: &str = "MSG_PREAMBLE";
+    const TEST_SIZE_FIELD_LEN: usize = 4;
+    const TEST_MAX_PACKAGE_SIZE: usize = 1024;
+
+    #[test]
+    fn sanity() {
+        // GIVEN
+        let test_packages = [
+            "{\"status\": \"SUCCESS\"}".as_bytes().to_vec(),
+            vec![0x01, 0x02, 0x03],
+        ];
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let packages_number = test_packages.len();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            for _ in 0..packages_number {
+                echo_package(&mut stream);
+            }
+        });
+
+        // WHEN
+        let port = generate_stream_port(&address, &Arc::new(AtomicUsize::new(0)));
+        let (mut reader, mut writer) = generate_reader_writer(&port);
+
+        // THEN
+        for test_package in &test_packages {
+            writer.write_data(test_package).unwrap();
+            assert_eq!(test_package, &reader.read_data().unwrap());
+        }
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn reconnect_after_connection_loss() {
+        // GIVEN
+        let test_package = vec![0x01, 0x02, 0x03];
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let server = thread::spawn(move || {
+            let (dropped_stream, _) = listener.accept().unwrap();
+            drop(dropped_stream);
+            let (mut stream, _) = listener.accept().unwrap();
+            echo_package(&mut stream);
+        });
+        let connections = Arc::new(AtomicUsize::new(0));
+
+        // WHEN
+        let port = generate_stream_port(&address, &connections);
+        let (mut reader, mut writer) = generate_reader_writer(&port);
+
+        // THEN
+        let lost_exchange = writer
+            .write_data(&test_package)
+            .and_then(|_| reader.read_data());
+        assert!(lost_exchange.is_err());
+        writer.write_data(&test_package).unwrap();
+        assert_eq!(test_package, reader.read_data().unwrap());
+        assert_eq!(2, connections.load(Ordering::SeqCst));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn connect_failure() {
+        // WHEN
+        let connect_result = StreamPort::<TcpStream>::new(|| Err(IpcError::Unavailable("no peer".into())));
+
+        // THEN
+        assert!(matches!(connect_result, Err(IpcError::Unavailable(_))));
+    }
+
+    fn echo_package(stream: &mut TcpStream) {
+        let mut decoder = SizedPackageDecoder::new(
+            TEST_PREAMBLE.as_bytes(),
+            Box::new(DefaultSizeDecoder::new(TEST_SIZE_FIELD_LEN).unwrap()),
+            None,
+            TEST_MAX_PACKAGE_SIZE,
+        );
+        let encoder = SizedPackageEncoder::new(
+            TEST_PREAMBLE.as_bytes(),
+            Box::new(DefaultSizeEncoder::new(TEST_SIZE_FIELD_LEN).unwrap()),
+            None,
+        );
+        let package = decoder.read_package(stream).unwrap();
+        stream.write_all(&encoder.encode_package(&package).unwrap()).unwrap();
+    }
+
+    fn generate_stream_port(address: &str, connections: &Arc<AtomicUsize>) -> StreamPort<TcpStream> {
+        let address = address.to_string();
+        let connections = connections.clone();
+        StreamPort::new(move || {
+            let stream = TcpStream::connect(&address)?;
+            stream.set_read_timeout(Some(Duration::from_millis(3000)))?;
+            connections.fetch_add(1, Ordering::SeqCst);
+            Ok(stream)
+        })
+        .unwrap()
+    }
+
+    fn generate_reader_writer(port: &StreamPort<TcpStream>) -> (StreamSizedPackageReader<TcpStream>, StreamSizedPackageWriter<TcpStream>) {
+        let reader = StreamSizedPackageReader::new(
+            port,
+            TEST_PREAMBLE.as_bytes(),
+            Box::new(DefaultSizeDecoder::new(TEST_SIZE_FIELD_LEN).unwrap()),
+            None,
+            TEST_MAX_PACKAGE_SIZE,
+        );
+        let writer = StreamSizedPackageWriter::new(
+            port,
+            TEST_PREAMBLE.as_bytes(),
+            Box::new(DefaultSizeEncoder::new(TEST_SIZE_FIELD_LEN).unwrap()),
+            None,
+        );
+        (reader, writer)
+    }
+}
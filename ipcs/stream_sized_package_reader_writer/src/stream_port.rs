@@ -0,0 +1,46 @@
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex};
+
+use ipc::IpcError;
+
+pub type StreamConnector<Stream> = dyn Fn() -> Result<Stream, IpcError> + Send + Sync;
+
+/// Connection to a stream socket shared by its readers and writers, `connect` opens a new connection.
+pub struct StreamPort<Stream> {
+    connect: Arc<StreamConnector<Stream>>,
+    stream: Arc<Mutex<Option<Stream>>>,
+}
+
+impl<Stream: Read + Write> StreamPort<Stream> {
+    pub fn new(connect: impl Fn() -> Result<Stream, IpcError> + Send + Sync + 'static) -> Result<Self, IpcError> {
+        let stream = connect()?;
+        Ok(Self {
+            connect: Arc::new(connect),
+            stream: Arc::new(Mutex::new(Some(stream))),
+        })
+    }
+
+    /// Runs `action` on the connected stream, reconnecting first if the previous connection was dropped.
+    /// A failed `action` drops the connection, so the next call starts on a fresh stream.
+    pub fn with_stream<T>(&mut self, action: impl FnOnce(&mut Stream) -> Result<T, IpcError>) -> Result<T, IpcError> {
+        let mut stream_handle = self.stream.lock().map_err(|err| IpcError::Unavailable(err.to_string()))?;
+        let stream = match stream_handle.as_mut() {
+            Some(stream) => stream,
+            None => stream_handle.insert((self.connect)()?),
+        };
+        let result = action(stream);
+        if result.is_err() {
+            *stream_handle = None;
+        }
+        result
+    }
+}
+
+impl<Stream> Clone for StreamPort<Stream> {
+    fn clone(&self) -> Self {
+        Self {
+            connect: self.connect.clone(),
+            stream: self.stream.clone(),
+        }
+    }
+}
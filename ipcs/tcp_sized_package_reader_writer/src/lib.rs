@@ -0,0 +1,62 @@
+use std::net::TcpStream;
+
+pub use ipc::{IpcError, IpcReader, IpcWriter};
+pub use stream_sized_package_reader_writer::{ChecksumCalculator, SizeDecoder, SizeEncoder, SizedPackageDecoder, SizedPackageEncoder};
+use stream_sized_package_reader_writer::{StreamSizedPackageReader, StreamSizedPackageWriter};
+pub use tcp_port::{TcpPort, open_tcp_port};
+
+pub type TcpSizedPackageReader = StreamSizedPackageReader<TcpStream>;
+pub type TcpSizedPackageWriter = StreamSizedPackageWriter<TcpStream>;
+
+mod tcp_port;
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn sanity() {
+        // GIVEN
+        let test_data = [0x01, 0x02, 0x03];
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut data = [0; 3];
+            stream.read_exact(&mut data).unwrap();
+            stream.write_all(&data).unwrap();
+        });
+
+        // WHEN
+        let mut port = open_tcp_port(&address, Duration::from_millis(500), Duration::from_millis(3000)).unwrap();
+
+        // THEN
+        let echoed_data = port
+            .with_stream(|stream| {
+                stream.write_all(&test_data)?;
+                let mut data = [0; 3];
+                stream.read_exact(&mut data)?;
+                Ok(data)
+            })
+            .unwrap();
+        assert_eq!(test_data, echoed_data);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn connect_failure() {
+        // GIVEN
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        drop(listener);
+
+        // THEN
+        let connect_result = open_tcp_port(&address, Duration::from_millis(500), Duration::from_millis(500));
+        assert!(matches!(connect_result, Err(IpcError::Io(_))));
+    }
+}
@@ -0,0 +1,29 @@
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use ipc::IpcError;
+use stream_sized_package_reader_writer::StreamPort;
+
+pub type TcpPort = StreamPort<TcpStream>;
+
+/// Connects to `address`, the port reconnects the same way once the connection is lost.
+pub fn open_tcp_port(address: &str, connect_timeout: Duration, response_timeout: Duration) -> Result<TcpPort, IpcError> {
+    let address = address.to_string();
+    StreamPort::new(move || connect(&address, connect_timeout, response_timeout))
+}
+
+fn connect(address: &str, connect_timeout: Duration, response_timeout: Duration) -> Result<TcpStream, IpcError> {
+    let mut last_error = IpcError::Unavailable(format!("no socket addresses resolved for {address}"));
+    for socket_address in address.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&socket_address, connect_timeout) {
+            Ok(stream) => {
+                stream.set_read_timeout(Some(response_timeout))?;
+                stream.set_write_timeout(Some(response_timeout))?;
+                stream.set_nodelay(true)?;
+                return Ok(stream);
+            },
+            Err(err) => last_error = err.into(),
+        }
+    }
+    Err(last_error)
+}
@@ -0,0 +1,247 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use ipc::ErrorChain;
+pub use ipc::{IpcError, IpcReader, IpcWriter};
+
+pub type RawFrameReader = dyn IpcReader<Vec<u8>, IpcError> + Send;
+pub type RawFrameWriter = dyn IpcWriter<Vec<u8>, IpcError> + Send;
+
+/// Renders a human readable view of a frame, e.g. a decoded protobuf message.
+pub trait FrameDescriber {
+    fn describe(&self, frame: &[u8]) -> String;
+}
+
+#[derive(Clone, Copy)]
+enum Direction {
+    Rx,
+    Tx,
+}
+
+impl Direction {
+    fn tag(&self) -> &'static str {
+        match self {
+            Self::Rx => "RX",
+            Self::Tx => "TX",
+        }
+    }
+}
+
+/// Shared log sink, timestamps are relative to the logger creation.
+#[derive(Clone)]
+pub struct TrafficLogger {
+    sink: Arc<Mutex<Box<dyn Write + Send>>>,
+    start: Instant,
+}
+
+impl TrafficLogger {
+    pub fn new(sink: Box<dyn Write + Send>) -> Self {
+        Self {
+            sink: Arc::new(Mutex::new(sink)),
+            start: Instant::now(),
+        }
+    }
+
+    pub fn stderr() -> Self {
+        Self::new(Box::new(std::io::stderr()))
+    }
+
+    fn log_frame(&self, direction: Direction, frame: &[u8], describer: Option<&(dyn FrameDescriber + Send + Sync)>) {
+        let mut entry = format!("{} {} {} bytes\n", self.timestamp(), direction.tag(), frame.len());
+        entry.push_str(&hex_dump(frame));
+        if let Some(describer) = describer {
+            entry.push_str(&format!("  decoded: {}\n", describer.describe(frame)));
+        }
+        self.write_entry(&entry);
+    }
+
+    fn log_error(&self, direction: Direction, err: &IpcError) {
+        self.write_entry(&format!("{} {} error: {}\n", self.timestamp(), direction.tag(), ErrorChain(err)));
+    }
+
+    fn timestamp(&self) -> String {
+        let elapsed = self.start.elapsed();
+        format!("[{:>6}.{:06}]", elapsed.as_secs(), elapsed.subsec_micros())
+    }
+
+    fn write_entry(&self, entry: &str) {
+        // Logging must never break the traffic it observes
+        if let Ok(mut sink) = self.sink.lock() {
+            let _ = sink.write_all(entry.as_bytes());
+            let _ = sink.flush();
+        }
+    }
+}
+
+/// Classic 16 bytes per line dump: offset, hex bytes and their printable ASCII.
+pub fn hex_dump(data: &[u8]) -> String {
+    const BYTES_PER_LINE: usize = 16;
+    let mut dump = String::new();
+    for (line_index, line) in data.chunks(BYTES_PER_LINE).enumerate() {
+        let hex: Vec<String> = (0..BYTES_PER_LINE)
+            .map(|i| line.get(i).map_or("  ".to_string(), |byte| format!("{byte:02x}")))
+            .collect();
+        let ascii: String = line
+            .iter()
+            .map(|byte| match byte.is_ascii_graphic() || *byte == b' ' {
+                true => *byte as char,
+                false => '.',
+            })
+            .collect();
+        dump.push_str(&format!(
+            "  {:04x}  {}  {}  |{ascii}|\n",
+            line_index * BYTES_PER_LINE,
+            hex[..BYTES_PER_LINE / 2].join(" "),
+            hex[BYTES_PER_LINE / 2..].join(" "),
+        ));
+    }
+    dump
+}
+
+pub struct TrafficLoggingReader {
+    inner: Box<RawFrameReader>,
+    logger: TrafficLogger,
+    describer: Option<Box<dyn FrameDescriber + Send + Sync>>,
+}
+
+impl TrafficLoggingReader {
+    pub fn new(
+        inner: Box<RawFrameReader>,
+        logger: &TrafficLogger,
+        describer: Option<Box<dyn FrameDescriber + Send + Sync>>,
+    ) -> Self {
+        Self {
+            inner,
+            logger: logger.clone(),
+            describer,
+        }
+    }
+}
+
+impl IpcReader<Vec<u8>, IpcError> for TrafficLoggingReader {
+    fn read_data(&mut self) -> Result<Vec<u8>, IpcError> {
+        let result = self.inner.read_data();
+        match &result {
+            Ok(frame) => self.logger.log_frame(Direction::Rx, frame, self.describer.as_deref()),
+            Err(err) => self.logger.log_error(Direction::Rx, err),
+        }
+        result
+    }
+}
+
+pub struct TrafficLoggingWriter {
+    inner: Box<RawFrameWriter>,
+    logger: TrafficLogger,
+    describer: Option<Box<dyn FrameDescriber + Send + Sync>>,
+}
+
+impl TrafficLoggingWriter {
+    pub fn new(
+        inner: Box<RawFrameWriter>,
+        logger: &TrafficLogger,
+        describer: Option<Box<dyn FrameDescriber + Send + Sync>>,
+    ) -> Self {
+        Self {
+            inner,
+            logger: logger.clone(),
+            describer,
+        }
+    }
+}
+
+impl IpcWriter<Vec<u8>, IpcError> for TrafficLoggingWriter {
+    fn write_data(&mut self, data: &Vec<u8>) -> Result<(), IpcError> {
+        self.logger.log_frame(Direction::Tx, data, self.describer.as_deref());
+        let result = self.inner.write_data(data);
+        if let Err(err) = &result {
+            self.logger.log_error(Direction::Tx, err);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_dump_sanity() {
+        // GIVEN
+        let test_data = b"{\"status\": \"SUCCESS\"}\x00\x01";
+
+        // THEN
+        let dump = hex_dump(test_data);
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(2, lines.len());
+        assert_eq!("  0000  7b 22 73 74 61 74 75 73  22 3a 20 22 53 55 43 43  |{\"status\": \"SUCC|", lines[0]);
+        assert_eq!("  0010  45 53 53 22 7d 00 01                              |ESS\"}..|", lines[1]);
+        assert!(hex_dump(&[]).is_empty());
+    }
+
+    #[test]
+    fn sanity() {
+        // GIVEN
+        let test_frame = b"ping".to_vec();
+        let log = SharedBuffer::default();
+
+        // WHEN
+        let logger = TrafficLogger::new(Box::new(log.clone()));
+        let mut writer = TrafficLoggingWriter::new(Box::new(FakeWriter), &logger, Some(Box::new(LengthDescriber)));
+        let mut reader = TrafficLoggingReader::new(Box::new(FakeReader), &logger, None);
+
+        // THEN
+        writer.write_data(&test_frame).unwrap();
+        assert!(reader.read_data().is_err());
+        let log = log.contents();
+        let lines: Vec<&str> = log.lines().collect();
+        assert_eq!(4, lines.len());
+        assert!(lines[0].ends_with("TX 4 bytes"));
+        assert!(lines[1].ends_with("|ping|"));
+        assert_eq!("  decoded: 4 bytes long", lines[2]);
+        assert!(lines[3].ends_with("RX error: operation timed out"));
+    }
+
+    struct LengthDescriber;
+
+    impl FrameDescriber for LengthDescriber {
+        fn describe(&self, frame: &[u8]) -> String {
+            format!("{} bytes long", frame.len())
+        }
+    }
+
+    struct FakeReader;
+
+    impl IpcReader<Vec<u8>, IpcError> for FakeReader {
+        fn read_data(&mut self) -> Result<Vec<u8>, IpcError> {
+            Err(IpcError::Timeout)
+        }
+    }
+
+    struct FakeWriter;
+
+    impl IpcWriter<Vec<u8>, IpcError> for FakeWriter {
+        fn write_data(&mut self, _data: &Vec<u8>) -> Result<(), IpcError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl SharedBuffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,88 @@
+const MAX_BLOCK_CODE: u8 = 0xFF;
+
+pub fn encode(data: &[u8]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(max_encoded_length(data.len()));
+    let mut code_index = 0;
+    let mut code: u8 = 1;
+    encoded.push(0);
+    for &byte in data {
+        if byte != 0 {
+            encoded.push(byte);
+            code += 1;
+        }
+        if byte == 0 || code == MAX_BLOCK_CODE {
+            encoded[code_index] = code;
+            code_index = encoded.len();
+            encoded.push(0);
+            code = 1;
+        }
+    }
+    encoded[code_index] = code;
+    encoded
+}
+
+pub fn decode(encoded: &[u8]) -> Result<Vec<u8>, String> {
+    let mut decoded = Vec::with_capacity(encoded.len());
+    let mut index = 0;
+    while index < encoded.len() {
+        let code = encoded[index];
+        if code == 0 {
+            return Err(format!("unexpected zero code byte at position {index}"));
+        }
+        let block_start = index + 1;
+        let block_end = index + code as usize;
+        if block_end > encoded.len() {
+            return Err(format!("truncated block at position {index}: code {code}, {} bytes left", encoded.len() - block_start));
+        }
+        let block = &encoded[block_start..block_end];
+        if block.contains(&0) {
+            return Err(format!("unexpected zero data byte in block at position {index}"));
+        }
+        decoded.extend_from_slice(block);
+        index = block_end;
+        if code != MAX_BLOCK_CODE && index < encoded.len() {
+            decoded.push(0);
+        }
+    }
+    Ok(decoded)
+}
+
+pub fn max_encoded_length(data_length: usize) -> usize {
+    data_length + data_length / (MAX_BLOCK_CODE as usize - 1) + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode_sanity() {
+        // GIVEN
+        let long_block: Vec<u8> = (1..=255).collect();
+        let test_cases: Vec<(Vec<u8>, Vec<u8>)> = vec![
+            (vec![], vec![0x01]),
+            (vec![0x00], vec![0x01, 0x01]),
+            (vec![0x00, 0x00], vec![0x01, 0x01, 0x01]),
+            (vec![0x00, 0x11, 0x00], vec![0x01, 0x02, 0x11, 0x01]),
+            (vec![0x11, 0x22, 0x00, 0x33], vec![0x03, 0x11, 0x22, 0x02, 0x33]),
+            (vec![0x11, 0x00, 0x00, 0x00], vec![0x02, 0x11, 0x01, 0x01, 0x01]),
+            (long_block.clone(), [vec![0xFF], long_block[..254].to_vec(), vec![0x02, 0xFF]].concat()),
+        ];
+
+        // THEN
+        for (data, expected_encoded) in &test_cases {
+            let encoded = encode(data);
+            assert_eq!(expected_encoded, &encoded);
+            assert!(encoded.len() <= max_encoded_length(data.len()));
+            assert_eq!(data, &decode(&encoded).unwrap());
+        }
+    }
+
+    #[test]
+    fn decode_invalid_data() {
+        // THEN
+        assert!(decode(&[0x03, 0x11]).is_err());
+        assert!(decode(&[0x02, 0x00]).is_err());
+        assert!(decode(&[0x00]).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+pub use ipc::{FramingError, IpcError, IpcReader, IpcWriter};
+pub use uart_delimited_package_reader_writer::ByteStuffing;
+use uart_delimited_package_reader_writer::{UartDelimitedPackageReader, UartDelimitedPackageWriter};
+pub use uart_port::UartPort;
+
+pub type UartCobsPackageReader = UartDelimitedPackageReader<Cobs>;
+pub type UartCobsPackageWriter = UartDelimitedPackageWriter<Cobs>;
+
+/// Consistent overhead byte stuffing, packages are delimited by a zero byte.
+pub struct Cobs;
+
+impl ByteStuffing for Cobs {
+    const DELIMITER: u8 = 0x00;
+    const NAME: &'static str = "COBS";
+
+    fn encode(data: &[u8]) -> Vec<u8> {
+        cobs::encode(data)
+    }
+
+    fn decode(encoded: &[u8]) -> Result<Vec<u8>, String> {
+        cobs::decode(encoded)
+    }
+
+    fn max_encoded_length(data_length: usize) -> usize {
+        cobs::max_encoded_length(data_length)
+    }
+}
+
+mod cobs;
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use serialport::TTYPort;
+
+    use super::*;
+
+    const TEST_MAX_PACKAGE_SIZE: usize = 1024;
+
+    #[test]
+    fn sanity() {
+        // GIVEN
+        let test_packages = [
+            vec![0x08, 0x00, 0x12, 0x00, 0x00],
+            "{\"status\": \"SUCCESS\"}".as_bytes().to_vec(),
+        ];
+        let (master, slave) = TTYPort::pair().unwrap();
+
+        // WHEN
+        let mut reader = UartCobsPackageReader::new(&UartPort::from_serial_port(Box::new(slave)), TEST_MAX_PACKAGE_SIZE);
+        let mut writer = UartCobsPackageWriter::new(&UartPort::from_serial_port(Box::new(master)));
+
+        // THEN
+        for test_package in &test_packages {
+            writer.write_data(test_package).unwrap();
+        }
+        for test_package in &test_packages {
+            assert_eq!(test_package, &reader.read_data().unwrap());
+        }
+    }
+
+    #[test]
+    fn resync_after_corrupted_package() {
+        // GIVEN
+        let test_package = vec![0x01, 0x00, 0x02];
+        let (master, slave) = TTYPort::pair().unwrap();
+
+        // WHEN
+        let mut reader = UartCobsPackageReader::new(&UartPort::from_serial_port(Box::new(slave)), 4);
+        let mut master_port = UartPort::from_serial_port(Box::new(master));
+        let mut writer = UartCobsPackageWriter::new(&master_port);
+        master_port.get_mut().unwrap().write_all(&[0x05, 0x01, Cobs::DELIMITER]).unwrap();
+        master_port.get_mut().unwrap().write_all(&[0x0A; 16]).unwrap();
+        writer.write_data(&vec![0x0B; 16]).unwrap();
+        writer.write_data(&test_package).unwrap();
+
+        // THEN
+        assert!(matches!(reader.read_data(), Err(IpcError::Framing(FramingError::Malformed(_)))));
+        assert!(matches!(reader.read_data(), Err(IpcError::Framing(FramingError::PackageTooLarge { .. }))));
+        assert_eq!(test_package, reader.read_data().unwrap());
+    }
+}
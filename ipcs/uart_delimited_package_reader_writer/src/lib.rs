@@ -0,0 +1,194 @@
+use std::io::Read;
+use std::marker::PhantomData;
+
+pub use ipc::{FramingError, IpcError, IpcReader, IpcWriter};
+pub use uart_port::UartPort;
+
+const READ_CHUNK_SIZE: usize = 256;
+
+/// Byte stuffing keeping `DELIMITER` out of the encoded data, so packages can be split on it.
+pub trait ByteStuffing {
+    const DELIMITER: u8;
+    /// Name of the encoding used in error messages.
+    const NAME: &'static str;
+    /// Whether a package also starts with the delimiter, flushing line noise collected by the receiver before it.
+    const LEADING_DELIMITER: bool = false;
+
+    fn encode(data: &[u8]) -> Vec<u8>;
+
+    fn decode(encoded: &[u8]) -> Result<Vec<u8>, String>;
+
+    fn max_encoded_length(data_length: usize) -> usize;
+}
+
+pub struct UartDelimitedPackageReader<Stuffing> {
+    port: UartPort,
+    max_package_size: usize,
+    received_data: Vec<u8>,
+    skip_until_delimiter: bool,
+    _stuffing: PhantomData<Stuffing>,
+}
+
+impl<Stuffing: ByteStuffing> UartDelimitedPackageReader<Stuffing> {
+    pub fn new(port: &UartPort, max_package_size: usize) -> Self {
+        Self {
+            port: port.clone(),
+            max_package_size,
+            received_data: vec![],
+            skip_until_delimiter: false,
+            _stuffing: PhantomData,
+        }
+    }
+
+    fn take_encoded_package(&mut self) -> Option<Vec<u8>> {
+        let delimiter_position = self.received_data
+            .iter()
+            .position(|byte| *byte == Stuffing::DELIMITER)?;
+        let mut encoded_package: Vec<u8> = self.received_data.drain(..=delimiter_position).collect();
+        encoded_package.pop();
+        Some(encoded_package)
+    }
+
+    fn package_too_large(&self, size: usize) -> IpcError {
+        FramingError::PackageTooLarge { size, max_size: self.max_package_size }.into()
+    }
+}
+
+impl<Stuffing: ByteStuffing> IpcReader<Vec<u8>, IpcError> for UartDelimitedPackageReader<Stuffing> {
+    fn read_data(&mut self) -> Result<Vec<u8>, IpcError> {
+        let max_encoded_size = Stuffing::max_encoded_length(self.max_package_size);
+        loop {
+            while let Some(encoded_package) = self.take_encoded_package() {
+                if self.skip_until_delimiter {
+                    self.skip_until_delimiter = false;
+                    continue;
+                }
+                if encoded_package.is_empty() {
+                    continue;
+                }
+                if encoded_package.len() > max_encoded_size {
+                    return Err(self.package_too_large(encoded_package.len()));
+                }
+                let package = Stuffing::decode(&encoded_package)
+                    .map_err(|err| FramingError::Malformed(format!("invalid {} encoding: {err}", Stuffing::NAME)))?;
+                if package.len() > self.max_package_size {
+                    return Err(self.package_too_large(package.len()));
+                }
+                return Ok(package);
+            }
+            if self.received_data.len() > max_encoded_size {
+                let received_size = self.received_data.len();
+                self.received_data.clear();
+                self.skip_until_delimiter = true;
+                return Err(self.package_too_large(received_size));
+            }
+            let mut chunk = [0; READ_CHUNK_SIZE];
+            let mut port_handle = self.port.get_mut_for_read()?;
+            let read_size = port_handle.read(&mut chunk)?;
+            self.received_data.extend_from_slice(&chunk[..read_size]);
+        }
+    }
+}
+
+pub struct UartDelimitedPackageWriter<Stuffing> {
+    port: UartPort,
+    _stuffing: PhantomData<Stuffing>,
+}
+
+impl<Stuffing: ByteStuffing> UartDelimitedPackageWriter<Stuffing> {
+    pub fn new(port: &UartPort) -> Self {
+        Self {
+            port: port.clone(),
+            _stuffing: PhantomData,
+        }
+    }
+}
+
+impl<Stuffing: ByteStuffing> IpcWriter<Vec<u8>, IpcError> for UartDelimitedPackageWriter<Stuffing> {
+    fn write_data(&mut self, data: &Vec<u8>) -> Result<(), IpcError> {
+        let mut package_data = Vec::with_capacity(Stuffing::max_encoded_length(data.len()) + 2);
+        if Stuffing::LEADING_DELIMITER {
+            package_data.push(Stuffing::DELIMITER);
+        }
+        package_data.extend_from_slice(&Stuffing::encode(data));
+        package_data.push(Stuffing::DELIMITER);
+        let mut port_handle = self.port.get_mut_for_write()?;
+        port_handle.write_all(&package_data)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use serialport::TTYPort;
+
+    use super::*;
+
+    const TEST_DELIMITER: u8 = b'\n';
+
+    #[test]
+    fn sanity() {
+        // GIVEN
+        let test_packages = [b"G00 X1".to_vec(), b"G01 Y2".to_vec()];
+        let (master, slave) = TTYPort::pair().unwrap();
+
+        // WHEN
+        let mut reader = UartDelimitedPackageReader::<LineStuffing>::new(&UartPort::from_serial_port(Box::new(slave)), 8);
+        let mut writer = UartDelimitedPackageWriter::<LineStuffing>::new(&UartPort::from_serial_port(Box::new(master)));
+
+        // THEN
+        for test_package in &test_packages {
+            writer.write_data(test_package).unwrap();
+        }
+        for test_package in &test_packages {
+            assert_eq!(test_package, &reader.read_data().unwrap());
+        }
+    }
+
+    #[test]
+    fn resync_after_oversized_data() {
+        // GIVEN
+        let test_package = b"G00".to_vec();
+        let (master, slave) = TTYPort::pair().unwrap();
+
+        // WHEN
+        let mut reader = UartDelimitedPackageReader::<LineStuffing>::new(&UartPort::from_serial_port(Box::new(slave)), 4);
+        let mut master_port = UartPort::from_serial_port(Box::new(master));
+        let mut writer = UartDelimitedPackageWriter::<LineStuffing>::new(&master_port);
+        master_port.get_mut().unwrap().write_all(b"G\t0\n").unwrap();
+        master_port.get_mut().unwrap().write_all(&[b'G'; 16]).unwrap();
+        writer.write_data(&test_package).unwrap();
+        writer.write_data(&test_package).unwrap();
+
+        // THEN
+        assert!(matches!(reader.read_data(), Err(IpcError::Framing(FramingError::Malformed(_)))));
+        // The oversized data swallowed the first package
+        assert!(matches!(reader.read_data(), Err(IpcError::Framing(FramingError::PackageTooLarge { .. }))));
+        assert_eq!(test_package, reader.read_data().unwrap());
+    }
+
+    /// Lines of text without tabs, nothing is escaped.
+    struct LineStuffing;
+
+    impl ByteStuffing for LineStuffing {
+        const DELIMITER: u8 = TEST_DELIMITER;
+        const NAME: &'static str = "line";
+
+        fn encode(data: &[u8]) -> Vec<u8> {
+            data.to_vec()
+        }
+
+        fn decode(encoded: &[u8]) -> Result<Vec<u8>, String> {
+            match encoded.iter().position(|byte| *byte == b'\t') {
+                Some(position) => Err(format!("tab at position {position}")),
+                None => Ok(encoded.to_vec()),
+            }
+        }
+
+        fn max_encoded_length(data_length: usize) -> usize {
+            data_length
+        }
+    }
+}
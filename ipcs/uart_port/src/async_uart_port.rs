@@ -0,0 +1,72 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use ipc::IpcError;
+use tokio::sync::{Mutex, MutexGuard};
+use tokio_serial::SerialPortBuilderExt;
+
+use crate::UartPortSettings;
+
+pub use tokio_serial::SerialStream;
+
+/// Non-blocking counterpart of `UartPort`, must be created within a tokio runtime.
+#[derive(Clone)]
+pub struct AsyncUartPort {
+    port: Arc<Mutex<SerialStream>>,
+    read_timeout: Duration,
+    write_timeout: Duration,
+}
+
+impl AsyncUartPort {
+    pub fn new(
+        port_name: &str,
+        baud: u32,
+        timeout: Duration,
+    ) -> Result<Self, IpcError> {
+        Self::with_settings(port_name, baud, &UartPortSettings::new(timeout))
+    }
+
+    /// Only the line settings and timeouts apply, the port is neither reopened nor run through an open sequence.
+    pub fn with_settings(
+        port_name: &str,
+        baud: u32,
+        settings: &UartPortSettings,
+    ) -> Result<Self, IpcError> {
+        let port_res = tokio_serial::new(port_name, baud)
+            .data_bits(settings.data_bits)
+            .stop_bits(settings.stop_bits)
+            .parity(settings.parity)
+            .flow_control(settings.flow_control)
+            .open_native_async();
+        match port_res {
+            Ok(port) => Ok(Self {
+                port: Arc::new(Mutex::new(port)),
+                read_timeout: settings.read_timeout,
+                write_timeout: settings.write_timeout,
+            }),
+            Err(err) => Err(IpcError::Unavailable(format!("failed to open serial port {}: {}", port_name, err))),
+        }
+    }
+
+    pub fn from_serial_stream(port: SerialStream, timeout: Duration) -> Self {
+        Self {
+            port: Arc::new(Mutex::new(port)),
+            read_timeout: timeout,
+            write_timeout: timeout,
+        }
+    }
+
+    /// Time allowed for a single read operation, the stream itself never times out.
+    pub fn read_timeout(&self) -> Duration {
+        self.read_timeout
+    }
+
+    /// Time allowed for a single write operation.
+    pub fn write_timeout(&self) -> Duration {
+        self.write_timeout
+    }
+
+    pub async fn get_mut(&mut self) -> MutexGuard<'_, SerialStream> {
+        self.port.lock().await
+    }
+}
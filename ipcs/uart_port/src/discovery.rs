@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use ipc::IpcError;
+
+const VENDOR_ID_PROPERTY: &str = "ID_VENDOR_ID";
+const PRODUCT_ID_PROPERTY: &str = "ID_MODEL_ID";
+const SERIAL_NUMBER_PROPERTY: &str = "ID_SERIAL_SHORT";
+
+/// A serial device known to udev along with all of its udev properties.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UartPortInfo {
+    pub port_name: String,
+    pub properties: HashMap<String, String>,
+}
+
+impl UartPortInfo {
+    pub fn vendor_id(&self) -> Option<&str> {
+        self.property(VENDOR_ID_PROPERTY)
+    }
+
+    pub fn product_id(&self) -> Option<&str> {
+        self.property(PRODUCT_ID_PROPERTY)
+    }
+
+    pub fn serial_number(&self) -> Option<&str> {
+        self.property(SERIAL_NUMBER_PROPERTY)
+    }
+
+    pub fn property(&self, name: &str) -> Option<&str> {
+        self.properties.get(name).map(String::as_str)
+    }
+}
+
+/// Picks a serial device by USB identity, unset fields match any device.
+/// Vendor and product IDs are hex strings as udev reports them, e.g. `2e8a`, compared case-insensitively.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UartPortSelector {
+    pub vendor_id: Option<String>,
+    pub product_id: Option<String>,
+    pub serial_number: Option<String>,
+    /// Any other udev properties the device must have, e.g. `ID_USB_INTERFACE_NUM`.
+    pub properties: HashMap<String, String>,
+}
+
+impl UartPortSelector {
+    pub fn matches(&self, port_info: &UartPortInfo) -> bool {
+        let id_matches = |expected: &Option<String>, actual: Option<&str>| match (expected, actual) {
+            (None, _) => true,
+            (Some(expected), Some(actual)) => expected.eq_ignore_ascii_case(actual),
+            (Some(_), None) => false,
+        };
+        id_matches(&self.vendor_id, port_info.vendor_id())
+            && id_matches(&self.product_id, port_info.product_id())
+            && self.serial_number.as_deref().is_none_or(|serial_number| port_info.serial_number() == Some(serial_number))
+            && self.properties.iter().all(|(name, value)| port_info.property(name) == Some(value.as_str()))
+    }
+}
+
+/// Lists the serial devices backed by hardware, virtual consoles are left out.
+pub fn list_ports() -> Result<Vec<UartPortInfo>, IpcError> {
+    let context = libudev::Context::new().map_err(udev_unavailable)?;
+    let mut enumerator = libudev::Enumerator::new(&context).map_err(udev_unavailable)?;
+    enumerator.match_subsystem("tty").map_err(udev_unavailable)?;
+    let ports = enumerator
+        .scan_devices()
+        .map_err(udev_unavailable)?
+        .filter(|device| device.parent().is_some())
+        .filter_map(|device| {
+            let port_name = device.devnode()?.to_string_lossy().into_owned();
+            let properties = device
+                .properties()
+                .map(|property| (
+                    property.name().to_string_lossy().into_owned(),
+                    property.value().to_string_lossy().into_owned(),
+                ))
+                .collect();
+            Some(UartPortInfo { port_name, properties })
+        })
+        .collect();
+    Ok(ports)
+}
+
+/// Returns the name of the only port matching `selector`, more than one match is an error as well.
+pub fn find_port(selector: &UartPortSelector) -> Result<String, IpcError> {
+    let mut matching_ports: Vec<UartPortInfo> = list_ports()?
+        .into_iter()
+        .filter(|port_info| selector.matches(port_info))
+        .collect();
+    match matching_ports.len() {
+        1 => Ok(matching_ports.remove(0).port_name),
+        0 => Err(IpcError::Unavailable(format!("no serial port matches {selector:?}"))),
+        _ => Err(IpcError::Unavailable(format!(
+            "several serial ports match {selector:?}: {}",
+            matching_ports.iter().map(|port_info| port_info.port_name.as_str()).collect::<Vec<_>>().join(", "),
+        ))),
+    }
+}
+
+fn udev_unavailable(err: libudev::Error) -> IpcError {
+    IpcError::Unavailable(format!("failed to query udev: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selector_matching() {
+        // GIVEN
+        let test_port_info = UartPortInfo {
+            port_name: "/dev/ttyACM0".into(),
+            properties: HashMap::from([
+                (VENDOR_ID_PROPERTY.to_string(), "2e8a".to_string()),
+                (PRODUCT_ID_PROPERTY.to_string(), "000a".to_string()),
+                (SERIAL_NUMBER_PROPERTY.to_string(), "E6614C311B7D5A2F".to_string()),
+                ("ID_USB_INTERFACE_NUM".to_string(), "00".to_string()),
+            ]),
+        };
+
+        // THEN
+        assert!(UartPortSelector::default().matches(&test_port_info));
+        assert!(UartPortSelector {
+            vendor_id: Some("2E8A".into()),
+            product_id: Some("000a".into()),
+            serial_number: Some("E6614C311B7D5A2F".into()),
+            properties: HashMap::from([("ID_USB_INTERFACE_NUM".to_string(), "00".to_string())]),
+        }.matches(&test_port_info));
+        assert!(!UartPortSelector { product_id: Some("0003".into()), ..Default::default() }.matches(&test_port_info));
+        assert!(!UartPortSelector { serial_number: Some("e6614c311b7d5a2f".into()), ..Default::default() }.matches(&test_port_info));
+        assert!(!UartPortSelector {
+            properties: HashMap::from([("ID_USB_DRIVER".to_string(), "cdc_acm".to_string())]),
+            ..Default::default()
+        }.matches(&test_port_info));
+    }
+}
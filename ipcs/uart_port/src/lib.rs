@@ -1,13 +1,66 @@
+use std::io;
+use std::ops::{Deref, DerefMut};
+use std::thread;
 use std::time::Duration;
 use std::sync::{Arc, Mutex, MutexGuard};
 
-use serialport::{DataBits, FlowControl, Parity, StopBits};
+use ipc::IpcError;
 
-pub use serialport::SerialPort;
+pub use discovery::{find_port, list_ports, UartPortInfo, UartPortSelector};
+pub use open_sequence::{OpenSequence, ResetPulse};
+pub use transactor::UartTransactor;
+pub use serialport::{DataBits, FlowControl, Parity, SerialPort, StopBits};
+#[cfg(feature = "async")]
+pub use async_uart_port::{AsyncUartPort, SerialStream};
 
+/// Opens the serial device, called again on every reconnect attempt.
+pub type SerialPortOpener = dyn Fn() -> Result<Box<dyn SerialPort>, IpcError> + Send + Sync;
+
+/// Serial line settings, `new` gives 8N1 without flow control and without reconnects.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UartPortSettings {
+    pub data_bits: DataBits,
+    pub parity: Parity,
+    pub stop_bits: StopBits,
+    pub flow_control: FlowControl,
+    pub read_timeout: Duration,
+    pub write_timeout: Duration,
+    pub reconnect: Option<ReconnectPolicy>,
+    pub open_sequence: Option<OpenSequence>,
+}
+
+impl UartPortSettings {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            data_bits: DataBits::Eight,
+            parity: Parity::None,
+            stop_bits: StopBits::One,
+            flow_control: FlowControl::None,
+            read_timeout: timeout,
+            write_timeout: timeout,
+            reconnect: None,
+            open_sequence: None,
+        }
+    }
+}
+
+/// Reopening attempts once the device is gone, the delay between them doubles up to `max_backoff`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReconnectPolicy {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub max_attempts: u32,
+}
+
+/// Serial port shared by readers and writers.
+/// With a reconnect policy, a port found dead is reopened before the next read or write,
+/// which then fails with `IpcError::Reconnected` so the caller can restore the peer state.
 #[derive(Clone)]
 pub struct UartPort {
-    port: Arc<Mutex<Box<dyn SerialPort>>>,
+    connection: Arc<Mutex<Connection>>,
+    transaction: Arc<Mutex<()>>,
+    read_timeout: Duration,
+    write_timeout: Duration,
 }
 
 impl UartPort {
@@ -15,26 +68,287 @@ impl UartPort {
         port_name: &str,
         baud: u32,
         timeout: Duration,
-    ) -> Result<Self, String> {
-        let port_res = serialport::new(port_name, baud)
-            .timeout(timeout)
-            .data_bits(DataBits::Eight)
-            .stop_bits(StopBits::One)
-            .parity(Parity::None)
-            .flow_control(FlowControl::None)
-            .open();
-        match port_res {
-            Ok(port) => Ok(Self {
-                port: Arc::new(Mutex::new(port)),
-            }),
-            Err(err) => Err(format!("failed to open serial port {}: {}", port_name, err)),
+    ) -> Result<Self, IpcError> {
+        Self::with_settings(port_name, baud, &UartPortSettings::new(timeout))
+    }
+
+    pub fn with_settings(
+        port_name: &str,
+        baud: u32,
+        settings: &UartPortSettings,
+    ) -> Result<Self, IpcError> {
+        let port_name = port_name.to_string();
+        let line_settings = settings.clone();
+        Self::with_opener(Box::new(move || open_port(&port_name, baud, &line_settings)), settings)
+    }
+
+    /// Opens the only port matching `selector`, see `find_port`.
+    /// The device is looked up again on reconnects, so it may come back under another name.
+    pub fn discover(
+        selector: &UartPortSelector,
+        baud: u32,
+        settings: &UartPortSettings,
+    ) -> Result<Self, IpcError> {
+        let selector = selector.clone();
+        let line_settings = settings.clone();
+        Self::with_opener(Box::new(move || open_port(&find_port(&selector)?, baud, &line_settings)), settings)
+    }
+
+    pub fn with_opener(opener: Box<SerialPortOpener>, settings: &UartPortSettings) -> Result<Self, IpcError> {
+        let port = opener()?;
+        Ok(Self {
+            connection: Arc::new(Mutex::new(Connection {
+                port,
+                opener: Some(opener),
+                reconnect_policy: settings.reconnect,
+                reconnects: 0,
+            })),
+            transaction: Arc::new(Mutex::new(())),
+            read_timeout: settings.read_timeout,
+            write_timeout: settings.write_timeout,
+        })
+    }
+
+    /// The port keeps its own timeout for both reads and writes and is never reopened.
+    pub fn from_serial_port(port: Box<dyn SerialPort>) -> Self {
+        let timeout = port.timeout();
+        Self {
+            connection: Arc::new(Mutex::new(Connection {
+                port,
+                opener: None,
+                reconnect_policy: None,
+                reconnects: 0,
+            })),
+            transaction: Arc::new(Mutex::new(())),
+            read_timeout: timeout,
+            write_timeout: timeout,
         }
     }
 
-    pub fn get_mut(&mut self) -> Result<MutexGuard<Box<dyn SerialPort>>, String> {
-        match self.port.lock() {
-            Ok(guard) => Ok(guard),
-            Err(err) => Err(err.to_string()),
+    /// Total number of times the device was opened again after it had gone.
+    pub fn reconnects(&self) -> usize {
+        self.connection.lock().map_or(0, |connection| connection.reconnects)
+    }
+
+    /// Keeps the other `UartTransactor`s of this port waiting while the guard lives.
+    /// Readers and writers used on their own don't take it and can still interleave with a transaction.
+    pub fn lock_transaction(&self) -> Result<MutexGuard<'_, ()>, IpcError> {
+        self.transaction.lock().map_err(|err| IpcError::Unavailable(err.to_string()))
+    }
+
+    pub fn get_mut(&mut self) -> Result<UartPortGuard<'_>, IpcError> {
+        match self.connection.lock() {
+            Ok(connection) => Ok(UartPortGuard { connection }),
+            Err(err) => Err(IpcError::Unavailable(err.to_string())),
         }
     }
-}
\ No newline at end of file
+
+    /// Locks the port with the read timeout applied.
+    pub fn get_mut_for_read(&mut self) -> Result<UartPortGuard<'_>, IpcError> {
+        let timeout = self.read_timeout;
+        self.get_mut_with_timeout(timeout)
+    }
+
+    /// Locks the port with the write timeout applied.
+    pub fn get_mut_for_write(&mut self) -> Result<UartPortGuard<'_>, IpcError> {
+        let timeout = self.write_timeout;
+        self.get_mut_with_timeout(timeout)
+    }
+
+    fn get_mut_with_timeout(&mut self, timeout: Duration) -> Result<UartPortGuard<'_>, IpcError> {
+        let mut guard = self.get_mut()?;
+        if guard.connection.reconnect_if_lost()? {
+            return Err(IpcError::Reconnected);
+        }
+        if guard.timeout() != timeout {
+            guard.set_timeout(timeout).map_err(io::Error::from)?;
+        }
+        Ok(guard)
+    }
+}
+
+/// Exclusive access to the serial port of a `UartPort`.
+pub struct UartPortGuard<'a> {
+    connection: MutexGuard<'a, Connection>,
+}
+
+impl Deref for UartPortGuard<'_> {
+    type Target = Box<dyn SerialPort>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.connection.port
+    }
+}
+
+impl DerefMut for UartPortGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.connection.port
+    }
+}
+
+struct Connection {
+    port: Box<dyn SerialPort>,
+    opener: Option<Box<SerialPortOpener>>,
+    reconnect_policy: Option<ReconnectPolicy>,
+    reconnects: usize,
+}
+
+impl Connection {
+    /// Returns `true` if the port was found dead and opened again.
+    fn reconnect_if_lost(&mut self) -> Result<bool, IpcError> {
+        let (Some(opener), Some(policy)) = (&self.opener, self.reconnect_policy) else {
+            return Ok(false);
+        };
+        // Fails on a hung up terminal, i.e. once the device is gone
+        if self.port.bytes_to_read().is_ok() {
+            return Ok(false);
+        }
+        let mut backoff = policy.initial_backoff;
+        for _ in 0..policy.max_attempts {
+            thread::sleep(backoff);
+            if let Ok(port) = opener() {
+                self.port = port;
+                self.reconnects += 1;
+                return Ok(true);
+            }
+            backoff = (backoff * 2).min(policy.max_backoff);
+        }
+        Err(IpcError::Unavailable(format!("serial device did not come back after {} attempts", policy.max_attempts)))
+    }
+}
+
+fn open_port(port_name: &str, baud: u32, settings: &UartPortSettings) -> Result<Box<dyn SerialPort>, IpcError> {
+    let mut port = serialport::new(port_name, baud)
+        .timeout(settings.read_timeout)
+        .data_bits(settings.data_bits)
+        .stop_bits(settings.stop_bits)
+        .parity(settings.parity)
+        .flow_control(settings.flow_control)
+        .open()
+        .map_err(|err| IpcError::Unavailable(format!("failed to open serial port {}: {}", port_name, err)))?;
+    if let Some(open_sequence) = &settings.open_sequence {
+        open_sequence::run_open_sequence(&mut *port, open_sequence)?;
+    }
+    Ok(port)
+}
+
+mod discovery;
+mod open_sequence;
+mod transactor;
+#[cfg(feature = "async")]
+mod async_uart_port;
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+
+    use serialport::TTYPort;
+
+    use super::*;
+
+    #[test]
+    fn timeouts() {
+        // GIVEN
+        let (_master, slave) = TTYPort::pair().unwrap();
+        let test_settings = UartPortSettings {
+            data_bits: DataBits::Seven,
+            parity: Parity::Even,
+            stop_bits: StopBits::Two,
+            read_timeout: Duration::from_millis(50),
+            write_timeout: Duration::from_millis(700),
+            ..UartPortSettings::new(Duration::from_secs(1))
+        };
+
+        // WHEN
+        let mut port = UartPort::with_settings(&slave.name().unwrap(), 115200, &test_settings).unwrap();
+
+        // THEN
+        // A pseudo terminal forces 8 data bits without parity, only the timeouts can be checked
+        assert_eq!(test_settings.read_timeout, port.get_mut_for_read().unwrap().timeout());
+        assert_eq!(test_settings.write_timeout, port.get_mut_for_write().unwrap().timeout());
+    }
+
+    #[test]
+    fn open_sequence() {
+        // GIVEN
+        let (mut master, slave) = TTYPort::pair().unwrap();
+        let test_settings = UartPortSettings {
+            open_sequence: Some(OpenSequence {
+                reset: None,
+                ready_marker: Some(b"READY".to_vec()),
+                ready_timeout: Duration::from_secs(2),
+            }),
+            ..UartPortSettings::new(Duration::from_millis(500))
+        };
+        master.write_all(b"stale bytes of an earlier session").unwrap();
+        let mut booting_master = master.try_clone_native().unwrap();
+        let boot = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            booting_master.write_all(b"pico boot log\r\nREA").unwrap();
+            thread::sleep(Duration::from_millis(20));
+            booting_master.write_all(b"DY\r\n").unwrap();
+        });
+
+        // WHEN
+        let mut port = UartPort::with_settings(&slave.name().unwrap(), 115200, &test_settings).unwrap();
+        boot.join().unwrap();
+
+        // THEN
+        master.write_all(&[0x2a]).unwrap();
+        let mut received = [0u8; 1];
+        port.get_mut_for_read().unwrap().read_exact(&mut received).unwrap();
+        assert_eq!([0x2a], received);
+    }
+
+    #[test]
+    fn empty_ready_marker() {
+        // GIVEN
+        let (_master, slave) = TTYPort::pair().unwrap();
+        let test_settings = UartPortSettings {
+            open_sequence: Some(OpenSequence {
+                reset: None,
+                ready_marker: Some(vec![]),
+                ready_timeout: Duration::from_millis(100),
+            }),
+            ..UartPortSettings::new(Duration::from_millis(100))
+        };
+
+        // WHEN
+        let result = UartPort::with_settings(&slave.name().unwrap(), 115200, &test_settings);
+
+        // THEN
+        assert!(matches!(result, Err(IpcError::Unavailable(_))));
+    }
+
+    #[test]
+    fn reconnect() {
+        // GIVEN
+        let (first_master, first_slave) = TTYPort::pair().unwrap();
+        let (mut second_master, second_slave) = TTYPort::pair().unwrap();
+        let slaves: Mutex<Vec<Box<dyn SerialPort>>> = Mutex::new(vec![Box::new(second_slave), Box::new(first_slave)]);
+        let test_settings = UartPortSettings {
+            reconnect: Some(ReconnectPolicy {
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(4),
+                max_attempts: 3,
+            }),
+            ..UartPortSettings::new(Duration::from_millis(100))
+        };
+
+        // WHEN
+        let opener = move || slaves.lock().unwrap().pop().ok_or(IpcError::Unavailable("unplugged".into()));
+        let mut port = UartPort::with_opener(Box::new(opener), &test_settings).unwrap();
+        assert!(port.get_mut_for_write().is_ok());
+        drop(first_master);
+
+        // THEN
+        assert!(matches!(port.get_mut_for_write(), Err(IpcError::Reconnected)));
+        assert_eq!(1, port.reconnects());
+        port.get_mut_for_write().unwrap().write_all(&[0x2a]).unwrap();
+        let mut received = [0u8; 1];
+        second_master.read_exact(&mut received).unwrap();
+        assert_eq!([0x2a], received);
+        drop(second_master);
+        assert!(matches!(port.get_mut_for_read(), Err(IpcError::Unavailable(_))));
+    }
+}
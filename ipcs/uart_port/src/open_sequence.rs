@@ -0,0 +1,84 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use ipc::IpcError;
+use serialport::{ClearBuffer, SerialPort};
+
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Steps run every time the device is opened, reconnects included, the buffers are always flushed last.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpenSequence {
+    pub reset: Option<ResetPulse>,
+    /// Bytes the MCU sends once it is ready, e.g. its boot banner or a raw ready frame.
+    pub ready_marker: Option<Vec<u8>>,
+    pub ready_timeout: Duration,
+}
+
+/// Deasserts the selected modem control lines for `duration`, which resets most boards.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResetPulse {
+    pub dtr: bool,
+    pub rts: bool,
+    pub duration: Duration,
+}
+
+pub(crate) fn run_open_sequence(port: &mut dyn SerialPort, sequence: &OpenSequence) -> Result<(), IpcError> {
+    if sequence.ready_marker.as_ref().is_some_and(Vec::is_empty) {
+        return Err(IpcError::Unavailable("the ready marker is empty".into()));
+    }
+    if let Some(reset) = &sequence.reset {
+        pulse_reset_lines(port, reset).map_err(|err| IpcError::Unavailable(format!("failed to reset the device: {err}")))?;
+    }
+    if let Some(ready_marker) = &sequence.ready_marker {
+        wait_for_marker(port, ready_marker, sequence.ready_timeout)?;
+    }
+    port.clear(ClearBuffer::All)
+        .map_err(|err| IpcError::Unavailable(format!("failed to flush the port buffers: {err}")))
+}
+
+fn pulse_reset_lines(port: &mut dyn SerialPort, reset: &ResetPulse) -> serialport::Result<()> {
+    set_reset_lines(port, reset, false)?;
+    thread::sleep(reset.duration);
+    set_reset_lines(port, reset, true)
+}
+
+fn set_reset_lines(port: &mut dyn SerialPort, reset: &ResetPulse, level: bool) -> serialport::Result<()> {
+    if reset.dtr {
+        port.write_data_terminal_ready(level)?;
+    }
+    if reset.rts {
+        port.write_request_to_send(level)?;
+    }
+    Ok(())
+}
+
+fn wait_for_marker(port: &mut dyn SerialPort, marker: &[u8], timeout: Duration) -> Result<(), IpcError> {
+    let deadline = Instant::now() + timeout;
+    let initial_timeout = port.timeout();
+    let mut received: Vec<u8> = vec![];
+    let mut chunk = [0u8; 256];
+    let result = loop {
+        let Some(time_left) = deadline.checked_duration_since(Instant::now()) else {
+            break Err(IpcError::Unavailable(format!("the device was not ready within {timeout:?}")));
+        };
+        if let Err(err) = port.set_timeout(time_left.min(READY_POLL_INTERVAL)) {
+            break Err(IpcError::Unavailable(err.to_string()));
+        }
+        match port.read(&mut chunk) {
+            Ok(chunk_size) => received.extend_from_slice(&chunk[..chunk_size]),
+            Err(err) => match IpcError::from(err) {
+                IpcError::Timeout => continue,
+                err => break Err(err),
+            },
+        }
+        if received.windows(marker.len()).any(|window| window == marker) {
+            break Ok(());
+        }
+        // Only a marker split across reads needs the earlier bytes
+        let keep_from = received.len().saturating_sub(marker.len());
+        received.drain(..keep_from);
+    };
+    port.set_timeout(initial_timeout).map_err(|err| IpcError::Unavailable(err.to_string()))?;
+    result
+}
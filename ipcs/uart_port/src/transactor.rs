@@ -0,0 +1,118 @@
+use std::ops::ControlFlow;
+
+use ipc::{IpcError, IpcReader, IpcTransactor, IpcWriter};
+
+use crate::UartPort;
+
+pub type RawFrameReader = dyn IpcReader<Vec<u8>, IpcError> + Send;
+pub type RawFrameWriter = dyn IpcWriter<Vec<u8>, IpcError> + Send;
+
+/// Runs request/response exchanges over a reader and a writer of `port` while holding its transaction lock,
+/// several transactors can then share one port across threads.
+pub struct UartTransactor {
+    port: UartPort,
+    reader: Box<RawFrameReader>,
+    writer: Box<RawFrameWriter>,
+}
+
+impl UartTransactor {
+    pub fn new(port: &UartPort, reader: Box<RawFrameReader>, writer: Box<RawFrameWriter>) -> Self {
+        Self {
+            port: port.clone(),
+            reader,
+            writer,
+        }
+    }
+}
+
+impl IpcTransactor<Vec<u8>, IpcError> for UartTransactor {
+    fn transact(&mut self, request: &Vec<u8>, on_response: &mut dyn FnMut(Vec<u8>) -> ControlFlow<()>) -> Result<(), IpcError> {
+        let _transaction = self.port.lock_transaction()?;
+        self.writer.write_data(request)?;
+        loop {
+            if on_response(self.reader.read_data()?).is_break() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+
+    use serialport::TTYPort;
+
+    use super::*;
+
+    #[test]
+    fn sanity() {
+        // GIVEN
+        let (_master, slave) = TTYPort::pair().unwrap();
+        let port = UartPort::from_serial_port(Box::new(slave));
+        let log = Arc::new(Mutex::new(vec![]));
+
+        // WHEN
+        let callers: Vec<_> = (0..2u8)
+            .map(|caller| {
+                let mut transactor = UartTransactor::new(
+                    &port,
+                    Box::new(LoggingReader { log: log.clone() }),
+                    Box::new(LoggingWriter { log: log.clone() }),
+                );
+                thread::spawn(move || {
+                    for _ in 0..10 {
+                        let mut responses = 0;
+                        transactor
+                            .transact(&vec![caller], &mut |_| {
+                                responses += 1;
+                                match responses {
+                                    2 => ControlFlow::Break(()),
+                                    _ => ControlFlow::Continue(()),
+                                }
+                            })
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        callers.into_iter().for_each(|caller| caller.join().unwrap());
+
+        // THEN
+        let log = log.lock().unwrap();
+        assert_eq!(60, log.len());
+        for transaction in log.chunks(3) {
+            assert_eq!(vec![("write", transaction[0].1), ("read", transaction[0].1), ("read", transaction[0].1)], transaction);
+        }
+    }
+
+    type Log = Arc<Mutex<Vec<(&'static str, u8)>>>;
+
+    struct LoggingReader {
+        log: Log,
+    }
+
+    impl IpcReader<Vec<u8>, IpcError> for LoggingReader {
+        fn read_data(&mut self) -> Result<Vec<u8>, IpcError> {
+            thread::sleep(Duration::from_millis(1));
+            let mut log = self.log.lock().unwrap();
+            let (_, caller) = *log.last().unwrap();
+            log.push(("read", caller));
+            Ok(vec![caller])
+        }
+    }
+
+    struct LoggingWriter {
+        log: Log,
+    }
+
+    impl IpcWriter<Vec<u8>, IpcError> for LoggingWriter {
+        fn write_data(&mut self, data: &Vec<u8>) -> Result<(), IpcError> {
+            self.log.lock().unwrap().push(("write", data[0]));
+            thread::sleep(Duration::from_millis(1));
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use ipc::{AsyncIpcReader, AsyncIpcWriter, IpcError};
+use sized_package_framing::{ChecksumCalculator, SizeDecoder, SizeEncoder, SizedPackageDecoder, SizedPackageEncoder};
+use tokio::io::AsyncWriteExt;
+use uart_port::AsyncUartPort;
+
+pub struct AsyncUartSizedPackageReader {
+    port: AsyncUartPort,
+    package_decoder: SizedPackageDecoder,
+}
+
+impl AsyncUartSizedPackageReader {
+    pub fn new(
+        port: &AsyncUartPort,
+        preamble: &[u8],
+        size_decoder: Box<dyn SizeDecoder + Send + Sync>,
+        checksum: Option<Box<dyn ChecksumCalculator + Send + Sync>>,
+        max_package_size: usize,
+    ) -> Self {
+        Self {
+            port: port.clone(),
+            package_decoder: SizedPackageDecoder::new(preamble, size_decoder, checksum, max_package_size),
+        }
+    }
+
+    /// Total number of bytes thrown away while searching for a preamble.
+    pub fn discarded_bytes(&self) -> usize {
+        self.package_decoder.discarded_bytes()
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncIpcReader<Vec<u8>, IpcError> for AsyncUartSizedPackageReader {
+    async fn read_data(&mut self) -> Result<Vec<u8>, IpcError> {
+        let timeout = self.port.read_timeout();
+        let mut port_handle = self.port.get_mut().await;
+        tokio::time::timeout(timeout, self.package_decoder.read_package_async(&mut *port_handle))
+            .await
+            .map_err(|_| IpcError::Timeout)?
+    }
+}
+
+pub struct AsyncUartSizedPackageWriter {
+    port: AsyncUartPort,
+    package_encoder: SizedPackageEncoder,
+}
+
+impl AsyncUartSizedPackageWriter {
+    pub fn new(
+        port: &AsyncUartPort,
+        preamble: &[u8],
+        size_encoder: Box<dyn SizeEncoder + Send + Sync>,
+        checksum: Option<Box<dyn ChecksumCalculator + Send + Sync>>,
+    ) -> Self {
+        Self {
+            port: port.clone(),
+            package_encoder: SizedPackageEncoder::new(preamble, size_encoder, checksum),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncIpcWriter<Vec<u8>, IpcError> for AsyncUartSizedPackageWriter {
+    async fn write_data(&mut self, data: &Vec<u8>) -> Result<(), IpcError> {
+        let package_data = self.package_encoder.encode_package(data)?;
+        let timeout = self.port.write_timeout();
+        let mut port_handle = self.port.get_mut().await;
+        tokio::time::timeout(timeout, port_handle.write_all(&package_data))
+            .await
+            .map_err(|_| IpcError::Timeout)?
+            .map_err(IpcError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use sized_package_framing::{Crc16Checksum, DefaultSizeDecoder, DefaultSizeEncoder};
+    use uart_port::SerialStream;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn sanity() {
+        // GIVEN
+        let test_preamble = "MSG_PREAMBLE";
+        let test_package = "{\"status\": \"SUCCESS\"}".as_bytes().to_vec();
+        let encoded_size_len = 4;
+        let timeout = Duration::from_millis(3000);
+        let (master, slave) = SerialStream::pair().unwrap();
+
+        // WHEN
+        let mut reader = AsyncUartSizedPackageReader::new(
+            &AsyncUartPort::from_serial_stream(slave, timeout),
+            test_preamble.as_bytes(),
+            Box::new(DefaultSizeDecoder::new(encoded_size_len).unwrap()),
+            Some(Box::new(Crc16Checksum::new())),
+            1024,
+        );
+        let mut writer = AsyncUartSizedPackageWriter::new(
+            &AsyncUartPort::from_serial_stream(master, timeout),
+            test_preamble.as_bytes(),
+            Box::new(DefaultSizeEncoder::new(encoded_size_len).unwrap()),
+            Some(Box::new(Crc16Checksum::new())),
+        );
+
+        // THEN
+        writer.write_data(&test_package).await.unwrap();
+        assert_eq!(test_package, reader.read_data().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn read_timeout() {
+        // GIVEN
+        let (_master, slave) = SerialStream::pair().unwrap();
+
+        // WHEN
+        let mut reader = AsyncUartSizedPackageReader::new(
+            &AsyncUartPort::from_serial_stream(slave, Duration::from_millis(100)),
+            "MSG_PREAMBLE".as_bytes(),
+            Box::new(DefaultSizeDecoder::new(4).unwrap()),
+            None,
+            1024,
+        );
+
+        // THEN
+        assert!(matches!(reader.read_data().await, Err(IpcError::Timeout)));
+    }
+}
@@ -1,11 +1,17 @@
-pub use ipc::{IpcReader, IpcWriter};
+pub use ipc::{FramingError, IpcError, IpcReader, IpcWriter};
 pub use uart_port::UartPort;
-pub use default_size_encoding::{DefaultSizeDecoder, DefaultSizeEncoder};
+#[cfg(feature = "async")]
+pub use async_reader_writer::{AsyncUartSizedPackageReader, AsyncUartSizedPackageWriter};
+#[cfg(feature = "async")]
+pub use uart_port::AsyncUartPort;
+pub use sized_package_framing::{
+    BigEndianSizeDecoder, BigEndianSizeEncoder, ChecksumCalculator, Crc16Checksum, Crc32Checksum, DefaultSizeDecoder,
+    DefaultSizeEncoder, SizeDecoder, SizeEncoder, SizedPackageDecoder, SizedPackageEncoder, VarintSizeDecoder, VarintSizeEncoder
+};
 
 pub struct UartSizedPackageReader {
     port: UartPort,
-    preamble: Vec<u8>,
-    size_decoder: Box<dyn SizeDecoder + Send + Sync>,
+    package_decoder: SizedPackageDecoder,
 }
 
 impl UartSizedPackageReader {
@@ -13,49 +19,31 @@ impl UartSizedPackageReader {
         port: &UartPort,
         preamble: &[u8],
         size_decoder: Box<dyn SizeDecoder + Send + Sync>,
+        checksum: Option<Box<dyn ChecksumCalculator + Send + Sync>>,
+        max_package_size: usize,
     ) -> Self {
         Self {
             port: port.clone(),
-            preamble: preamble.to_vec(),
-            size_decoder,
+            package_decoder: SizedPackageDecoder::new(preamble, size_decoder, checksum, max_package_size),
         }
     }
-}
 
-pub trait SizeDecoder {
-    fn raw_data_size(&self) -> usize;
-    fn decode(&self, raw_data: &[u8]) -> Result<usize, String>;
+    /// Total number of bytes thrown away while searching for a preamble.
+    pub fn discarded_bytes(&self) -> usize {
+        self.package_decoder.discarded_bytes()
+    }
 }
 
-impl IpcReader<Vec<u8>, String> for UartSizedPackageReader {
-    fn read_data(&mut self) -> Result<Vec<u8>, String> {
-        let preamble_size = self.preamble.len();
-        let encoded_size_length = self.size_decoder.raw_data_size();
-        let header_size = preamble_size + encoded_size_length;
-        
-        let mut port_handle = self.port.get_mut()?;
-        
-        let mut header_buff = vec![0; header_size];
-        let read_result = port_handle.read_exact(header_buff.as_mut_slice());
-        if read_result.is_err() {
-            return Err(format!("failed to read package header: {read_result:?}"));
-        }
-        if self.preamble != header_buff[0..preamble_size] {
-            return Err(format!("invalid preamble received: {:?}", &header_buff[0..preamble_size]));
-        }
-        let package_size = self.size_decoder.decode(&header_buff[preamble_size..(preamble_size + encoded_size_length)])?;
-        let mut data_buff = vec![0; package_size];
-        if port_handle.read_exact(data_buff.as_mut_slice()).is_err() {
-            return Err("failed to read package data".to_string());
-        }
-        Ok(data_buff)
+impl IpcReader<Vec<u8>, IpcError> for UartSizedPackageReader {
+    fn read_data(&mut self) -> Result<Vec<u8>, IpcError> {
+        let mut port_handle = self.port.get_mut_for_read()?;
+        self.package_decoder.read_package(&mut **port_handle)
     }
 }
 
 pub struct UartSizedPackageWriter {
     port: UartPort,
-    preamble: Vec<u8>,
-    size_encoder: Box<dyn SizeEncoder + Send + Sync>,
+    package_encoder: SizedPackageEncoder,
 }
 
 
@@ -64,62 +52,81 @@ impl UartSizedPackageWriter {
         port: &UartPort,
         preamble: &[u8],
         size_encoder: Box<dyn SizeEncoder + Send + Sync>,
+        checksum: Option<Box<dyn ChecksumCalculator + Send + Sync>>,
     ) -> Self {
         Self {
             port: port.clone(),
-            preamble: preamble.to_vec(),
-            size_encoder,
+            package_encoder: SizedPackageEncoder::new(preamble, size_encoder, checksum),
         }
     }
 }
 
-pub trait SizeEncoder {
-    fn encode(&self, size: usize) -> Result<Vec<u8>, String>;
-}
-
-impl IpcWriter<Vec<u8>, String> for UartSizedPackageWriter {
-    fn write_data(&mut self, data: &Vec<u8>) -> Result<(), String> {
-        let mut package_data = vec![];
-        package_data.extend_from_slice(&self.preamble);
-        let encoded_size = self.size_encoder.encode(data.len())?;
-        package_data.extend_from_slice(&encoded_size);
-        package_data.extend_from_slice(data);
-        let mut port_handle = self.port.get_mut()?;
-        if port_handle.write_all(&package_data).is_err() {
-            return Err("failed to send package data".to_string());
-        }
+impl IpcWriter<Vec<u8>, IpcError> for UartSizedPackageWriter {
+    fn write_data(&mut self, data: &Vec<u8>) -> Result<(), IpcError> {
+        let package_data = self.package_encoder.encode_package(data)?;
+        let mut port_handle = self.port.get_mut_for_write()?;
+        port_handle.write_all(&package_data)?;
         Ok(())
     }
 }
 
-mod default_size_encoding;
+#[cfg(feature = "async")]
+mod async_reader_writer;
 
 #[cfg(test)]
 mod tests {
+    use std::io::Write;
     use std::time::Duration;
 
+    use serialport::TTYPort;
+    use virtual_serial_port::{FakeMcu, VirtualSerialPort};
+
     use super::*;
 
+    const TEST_MAX_PACKAGE_SIZE: usize = 1024;
+
     #[test]
-    #[ignore = "WITH MCU CONNECTED ONLY"]
     fn new_sanity() {
         // GIVEN
-        let port_name = "/dev/ttyACM0";
+        let virtual_port = VirtualSerialPort::new().unwrap();
         let test_preamble = "MSG_PREAMBLE";
         let test_package = "{\"direction\": 0, \"steps_number\": 1000, \"step_duration_ms\": 10}";
+        let test_response = "{\"status\": \"SUCCESS\"}";
         let encoded_size_len = 4;
+        let fake_mcu = FakeMcu::spawn(
+            Box::new(UartSizedPackageReader::new(
+                virtual_port.mcu_port(),
+                test_preamble.as_bytes(),
+                Box::new(DefaultSizeDecoder::new(encoded_size_len).unwrap()),
+                None,
+                TEST_MAX_PACKAGE_SIZE,
+            )),
+            Box::new(UartSizedPackageWriter::new(
+                virtual_port.mcu_port(),
+                test_preamble.as_bytes(),
+                Box::new(DefaultSizeEncoder::new(encoded_size_len).unwrap()),
+                None,
+            )),
+            Box::new(move |request: Vec<u8>| {
+                assert_eq!(test_package.as_bytes(), request.as_slice());
+                Ok(vec![test_response.as_bytes().to_vec()])
+            }),
+        );
 
         // WHEN
-        let uart_port = generate_uart(port_name);
+        let uart_port = generate_uart(virtual_port.port_name());
         let mut reader = UartSizedPackageReader::new(
             &uart_port,
             test_preamble.as_bytes(),
-            Box::new(DefaultSizeDecoder::new(encoded_size_len)),
+            Box::new(DefaultSizeDecoder::new(encoded_size_len).unwrap()),
+            None,
+            TEST_MAX_PACKAGE_SIZE,
         );
         let mut writer = UartSizedPackageWriter::new(
             &uart_port,
             test_preamble.as_bytes(),
-            Box::new(DefaultSizeEncoder::new(encoded_size_len)),
+            Box::new(DefaultSizeEncoder::new(encoded_size_len).unwrap()),
+            None,
         );
 
         // THEN
@@ -128,8 +135,159 @@ mod tests {
 
         let read_result = reader.read_data();
         assert!(read_result.is_ok());
-        let read_data = read_result.unwrap();
-        println!("Received data: {:?}", String::from_utf8(read_data).unwrap());
+        assert_eq!(test_response.as_bytes(), read_result.unwrap().as_slice());
+        assert_eq!(1, fake_mcu.stop().unwrap());
+    }
+
+    #[test]
+    fn checksum_sanity() {
+        // GIVEN
+        let test_preamble = "MSG_PREAMBLE";
+        let test_package = "{\"status\": \"SUCCESS\"}";
+        let encoded_size_len = 4;
+        let (master, slave) = TTYPort::pair().unwrap();
+
+        // WHEN
+        let mut reader = UartSizedPackageReader::new(
+            &UartPort::from_serial_port(Box::new(slave)),
+            test_preamble.as_bytes(),
+            Box::new(DefaultSizeDecoder::new(encoded_size_len).unwrap()),
+            Some(Box::new(Crc32Checksum::new())),
+            TEST_MAX_PACKAGE_SIZE,
+        );
+        let mut writer = UartSizedPackageWriter::new(
+            &UartPort::from_serial_port(Box::new(master)),
+            test_preamble.as_bytes(),
+            Box::new(DefaultSizeEncoder::new(encoded_size_len).unwrap()),
+            Some(Box::new(Crc32Checksum::new())),
+        );
+
+        // THEN
+        writer.write_data(&test_package.as_bytes().to_vec()).unwrap();
+        let read_data = reader.read_data().unwrap();
+        assert_eq!(test_package.as_bytes(), read_data.as_slice());
+    }
+
+    #[test]
+    fn checksum_mismatch() {
+        // GIVEN
+        let test_preamble = "MSG_PREAMBLE";
+        let test_package = "{\"status\": \"SUCCESS\"}";
+        let encoded_size_len = 4;
+        let (mut master, slave) = TTYPort::pair().unwrap();
+        let mut corrupted_frame = vec![];
+        corrupted_frame.extend_from_slice(test_preamble.as_bytes());
+        corrupted_frame.extend_from_slice(&DefaultSizeEncoder::new(encoded_size_len).unwrap().encode(test_package.len()).unwrap());
+        corrupted_frame.extend_from_slice(test_package.as_bytes());
+        corrupted_frame.extend_from_slice(&Crc16Checksum::new().calculate(b"unrelated data"));
+
+        // WHEN
+        let mut reader = UartSizedPackageReader::new(
+            &UartPort::from_serial_port(Box::new(slave)),
+            test_preamble.as_bytes(),
+            Box::new(DefaultSizeDecoder::new(encoded_size_len).unwrap()),
+            Some(Box::new(Crc16Checksum::new())),
+            TEST_MAX_PACKAGE_SIZE,
+        );
+        master.write_all(&corrupted_frame).unwrap();
+
+        // THEN
+        let read_result = reader.read_data();
+        assert!(matches!(read_result, Err(IpcError::Framing(FramingError::ChecksumMismatch { .. }))));
+    }
+
+    #[test]
+    fn preamble_resync() {
+        // GIVEN
+        let test_preamble = "MSG_PREAMBLE";
+        let test_package = "{\"status\": \"SUCCESS\"}";
+        let test_garbage = "\0MSG_PRE MSG_";
+        let encoded_size_len = 4;
+        let (master, slave) = TTYPort::pair().unwrap();
+
+        // WHEN
+        let mut reader = UartSizedPackageReader::new(
+            &UartPort::from_serial_port(Box::new(slave)),
+            test_preamble.as_bytes(),
+            Box::new(DefaultSizeDecoder::new(encoded_size_len).unwrap()),
+            None,
+            TEST_MAX_PACKAGE_SIZE,
+        );
+        let mut master_port = UartPort::from_serial_port(Box::new(master));
+        let mut writer = UartSizedPackageWriter::new(
+            &master_port,
+            test_preamble.as_bytes(),
+            Box::new(DefaultSizeEncoder::new(encoded_size_len).unwrap()),
+            None,
+        );
+        master_port.get_mut().unwrap().write_all(test_garbage.as_bytes()).unwrap();
+        writer.write_data(&test_package.as_bytes().to_vec()).unwrap();
+        writer.write_data(&test_package.as_bytes().to_vec()).unwrap();
+
+        // THEN
+        assert_eq!(test_package.as_bytes(), reader.read_data().unwrap().as_slice());
+        assert_eq!(test_garbage.len(), reader.discarded_bytes());
+        assert_eq!(test_package.as_bytes(), reader.read_data().unwrap().as_slice());
+        assert_eq!(test_garbage.len(), reader.discarded_bytes());
+    }
+
+    #[test]
+    fn package_size_limit() {
+        // GIVEN
+        let test_preamble = "MSG_PREAMBLE";
+        let encoded_size_len = 4;
+        let (mut master, slave) = TTYPort::pair().unwrap();
+        let mut oversized_header = vec![];
+        oversized_header.extend_from_slice(test_preamble.as_bytes());
+        oversized_header.extend_from_slice(&DefaultSizeEncoder::new(encoded_size_len).unwrap().encode(u32::MAX as usize).unwrap());
+
+        // WHEN
+        let mut reader = UartSizedPackageReader::new(
+            &UartPort::from_serial_port(Box::new(slave)),
+            test_preamble.as_bytes(),
+            Box::new(DefaultSizeDecoder::new(encoded_size_len).unwrap()),
+            None,
+            TEST_MAX_PACKAGE_SIZE,
+        );
+        master.write_all(&oversized_header).unwrap();
+
+        // THEN
+        let read_result = reader.read_data();
+        assert!(matches!(
+            read_result,
+            Err(IpcError::Framing(FramingError::PackageTooLarge { max_size: TEST_MAX_PACKAGE_SIZE, .. }))
+        ));
+    }
+
+    #[test]
+    fn varint_size_sanity() {
+        // GIVEN
+        let test_preamble = "MSG_PREAMBLE";
+        let test_packages = [vec![0x11; 3], vec![0x22; 300]];
+        let (master, slave) = TTYPort::pair().unwrap();
+
+        // WHEN
+        let mut reader = UartSizedPackageReader::new(
+            &UartPort::from_serial_port(Box::new(slave)),
+            test_preamble.as_bytes(),
+            Box::new(VarintSizeDecoder::new()),
+            Some(Box::new(Crc16Checksum::new())),
+            TEST_MAX_PACKAGE_SIZE,
+        );
+        let mut writer = UartSizedPackageWriter::new(
+            &UartPort::from_serial_port(Box::new(master)),
+            test_preamble.as_bytes(),
+            Box::new(VarintSizeEncoder::new()),
+            Some(Box::new(Crc16Checksum::new())),
+        );
+
+        // THEN
+        for test_package in &test_packages {
+            writer.write_data(test_package).unwrap();
+        }
+        for test_package in &test_packages {
+            assert_eq!(test_package, &reader.read_data().unwrap());
+        }
     }
 
     fn generate_uart(port_name: &str) -> UartPort {
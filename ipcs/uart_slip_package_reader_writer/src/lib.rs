@@ -0,0 +1,85 @@
+pub use ipc::{FramingError, IpcError, IpcReader, IpcWriter};
+pub use uart_delimited_package_reader_writer::ByteStuffing;
+use uart_delimited_package_reader_writer::{UartDelimitedPackageReader, UartDelimitedPackageWriter};
+pub use uart_port::UartPort;
+
+pub type UartSlipPackageReader = UartDelimitedPackageReader<Slip>;
+pub type UartSlipPackageWriter = UartDelimitedPackageWriter<Slip>;
+
+/// SLIP byte stuffing (RFC 1055), packages are delimited by END.
+pub struct Slip;
+
+impl ByteStuffing for Slip {
+    const DELIMITER: u8 = slip::END;
+    const NAME: &'static str = "SLIP";
+    // A leading END flushes any line noise accumulated by the receiver (RFC 1055)
+    const LEADING_DELIMITER: bool = true;
+
+    fn encode(data: &[u8]) -> Vec<u8> {
+        slip::encode(data)
+    }
+
+    fn decode(encoded: &[u8]) -> Result<Vec<u8>, String> {
+        slip::decode(encoded)
+    }
+
+    fn max_encoded_length(data_length: usize) -> usize {
+        // Every data byte takes at most two bytes on the wire
+        2 * data_length
+    }
+}
+
+mod slip;
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use serialport::TTYPort;
+
+    use super::*;
+
+    const TEST_MAX_PACKAGE_SIZE: usize = 1024;
+
+    #[test]
+    fn sanity() {
+        // GIVEN
+        let test_packages = [
+            vec![0x08, slip::END, 0x12, 0xDB, 0x00],
+            "{\"status\": \"SUCCESS\"}".as_bytes().to_vec(),
+        ];
+        let (master, slave) = TTYPort::pair().unwrap();
+
+        // WHEN
+        let mut reader = UartSlipPackageReader::new(&UartPort::from_serial_port(Box::new(slave)), TEST_MAX_PACKAGE_SIZE);
+        let mut writer = UartSlipPackageWriter::new(&UartPort::from_serial_port(Box::new(master)));
+
+        // THEN
+        for test_package in &test_packages {
+            writer.write_data(test_package).unwrap();
+        }
+        for test_package in &test_packages {
+            assert_eq!(test_package, &reader.read_data().unwrap());
+        }
+    }
+
+    #[test]
+    fn framing_errors() {
+        // GIVEN
+        let test_package = vec![0x01, 0x02, 0x03];
+        let (master, slave) = TTYPort::pair().unwrap();
+
+        // WHEN
+        let mut reader = UartSlipPackageReader::new(&UartPort::from_serial_port(Box::new(slave)), 4);
+        let mut master_port = UartPort::from_serial_port(Box::new(master));
+        let mut writer = UartSlipPackageWriter::new(&master_port);
+        master_port.get_mut().unwrap().write_all(&[0x01, 0xDB, 0x02, slip::END]).unwrap();
+        writer.write_data(&vec![0x0B; 16]).unwrap();
+        writer.write_data(&test_package).unwrap();
+
+        // THEN
+        assert!(matches!(reader.read_data(), Err(IpcError::Framing(FramingError::Malformed(_)))));
+        assert!(matches!(reader.read_data(), Err(IpcError::Framing(FramingError::PackageTooLarge { .. }))));
+        assert_eq!(test_package, reader.read_data().unwrap());
+    }
+}
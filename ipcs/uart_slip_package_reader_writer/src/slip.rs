@@ -0,0 +1,65 @@
+pub const END: u8 = 0xC0;
+const ESC: u8 = 0xDB;
+const ESC_END: u8 = 0xDC;
+const ESC_ESC: u8 = 0xDD;
+
+pub fn encode(data: &[u8]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(data.len());
+    for &byte in data {
+        match byte {
+            END => encoded.extend_from_slice(&[ESC, ESC_END]),
+            ESC => encoded.extend_from_slice(&[ESC, ESC_ESC]),
+            any_other => encoded.push(any_other),
+        }
+    }
+    encoded
+}
+
+pub fn decode(encoded: &[u8]) -> Result<Vec<u8>, String> {
+    let mut decoded = Vec::with_capacity(encoded.len());
+    let mut bytes = encoded.iter().enumerate();
+    while let Some((_, &byte)) = bytes.next() {
+        if byte != ESC {
+            decoded.push(byte);
+            continue;
+        }
+        match bytes.next() {
+            Some((_, &ESC_END)) => decoded.push(END),
+            Some((_, &ESC_ESC)) => decoded.push(ESC),
+            Some((index, any_other)) => return Err(format!("invalid escape sequence at position {index}: 0x{any_other:02X}")),
+            None => return Err("package ends with an incomplete escape sequence".to_string()),
+        }
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode_sanity() {
+        // GIVEN
+        let test_cases: Vec<(Vec<u8>, Vec<u8>)> = vec![
+            (vec![], vec![]),
+            (vec![0x01, 0x02], vec![0x01, 0x02]),
+            (vec![END], vec![ESC, ESC_END]),
+            (vec![ESC], vec![ESC, ESC_ESC]),
+            (vec![0x01, END, ESC, 0x02], vec![0x01, ESC, ESC_END, ESC, ESC_ESC, 0x02]),
+        ];
+
+        // THEN
+        for (data, expected_encoded) in &test_cases {
+            let encoded = encode(data);
+            assert_eq!(expected_encoded, &encoded);
+            assert_eq!(data, &decode(&encoded).unwrap());
+        }
+    }
+
+    #[test]
+    fn decode_invalid_data() {
+        // THEN
+        assert!(decode(&[0x01, ESC, 0x02]).is_err());
+        assert!(decode(&[0x01, ESC]).is_err());
+    }
+}
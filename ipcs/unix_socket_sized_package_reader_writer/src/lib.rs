@@ -0,0 +1,61 @@
+use std::os::unix::net::UnixStream;
+
+pub use ipc::{IpcError, IpcReader, IpcWriter};
+pub use stream_sized_package_reader_writer::{ChecksumCalculator, SizeDecoder, SizeEncoder, SizedPackageDecoder, SizedPackageEncoder};
+use stream_sized_package_reader_writer::{StreamSizedPackageReader, StreamSizedPackageWriter};
+pub use unix_socket_port::{UnixSocketPort, open_unix_socket_port};
+
+pub type UnixSocketSizedPackageReader = StreamSizedPackageReader<UnixStream>;
+pub type UnixSocketSizedPackageWriter = StreamSizedPackageWriter<UnixStream>;
+
+mod unix_socket_port;
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixListener;
+    use std::thread;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn sanity() {
+        // GIVEN
+        let test_data = [0x01, 0x02, 0x03];
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let socket_path = tmp_dir.path().join("mcu.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut data = [0; 3];
+            stream.read_exact(&mut data).unwrap();
+            stream.write_all(&data).unwrap();
+        });
+
+        // WHEN
+        let mut port = open_unix_socket_port(&socket_path, Duration::from_millis(3000)).unwrap();
+
+        // THEN
+        let echoed_data = port
+            .with_stream(|stream| {
+                stream.write_all(&test_data)?;
+                let mut data = [0; 3];
+                stream.read_exact(&mut data)?;
+                Ok(data)
+            })
+            .unwrap();
+        assert_eq!(test_data, echoed_data);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn connect_failure() {
+        // GIVEN
+        let tmp_dir = tempfile::tempdir().unwrap();
+
+        // THEN
+        let connect_result = open_unix_socket_port(&tmp_dir.path().join("missing.sock"), Duration::from_millis(500));
+        assert!(matches!(connect_result, Err(IpcError::Io(_))));
+    }
+}
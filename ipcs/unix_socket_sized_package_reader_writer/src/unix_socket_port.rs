@@ -0,0 +1,21 @@
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::time::Duration;
+
+use ipc::IpcError;
+use stream_sized_package_reader_writer::StreamPort;
+
+pub type UnixSocketPort = StreamPort<UnixStream>;
+
+/// Connects to the socket at `socket_path`, the port reconnects the same way once the connection is lost.
+pub fn open_unix_socket_port(socket_path: &Path, response_timeout: Duration) -> Result<UnixSocketPort, IpcError> {
+    let socket_path = socket_path.to_path_buf();
+    StreamPort::new(move || connect(&socket_path, response_timeout))
+}
+
+fn connect(socket_path: &Path, response_timeout: Duration) -> Result<UnixStream, IpcError> {
+    let stream = UnixStream::connect(socket_path)?;
+    stream.set_read_timeout(Some(response_timeout))?;
+    stream.set_write_timeout(Some(response_timeout))?;
+    Ok(stream)
+}
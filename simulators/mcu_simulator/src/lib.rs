@@ -0,0 +1,230 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use ipc::ErrorChain;
+pub use ipc::{IpcError, IpcReader, IpcWriter};
+use movement_data::{Axis, AxisConfig, Correlated, MovementApiRequest, MovementApiResponse, StatusCode, Vector};
+use movement_service_client::{DataTransformer, TransformError};
+
+pub type RequestParser = dyn DataTransformer<Vec<u8>, Correlated<MovementApiRequest>, TransformError> + Send;
+pub type ResponseSerializer = dyn DataTransformer<Correlated<MovementApiResponse>, Vec<u8>, TransformError> + Send;
+
+const AXES: [Axis; 3] = [Axis::X, Axis::Y, Axis::Z];
+
+/// Misbehaviour applied to the next handled request instead of a regular response.
+#[derive(Clone, Debug)]
+pub enum Fault {
+    /// The request is rejected with the given message, the axes do not move.
+    Failure(String),
+    /// The request is executed but never answered, so the client runs into a read timeout.
+    DropResponse,
+    /// The request is executed and answered with the given bytes.
+    Garbage(Vec<u8>),
+}
+
+/// A linear movement executed by the simulator.
+#[derive(Clone, Debug)]
+pub struct SimulatedMovement {
+    pub steps: Vector<i64>,
+    pub duration: Duration,
+}
+
+/// Simulated MCU shared by `SimulatorReader` and `SimulatorWriter`.
+/// Every written frame is handled right away, its response is queued for the reader.
+#[derive(Clone)]
+pub struct McuSimulator {
+    mcu: Arc<Mutex<Mcu>>,
+}
+
+impl McuSimulator {
+    pub fn new(request_parser: Box<RequestParser>, response_serializer: Box<ResponseSerializer>) -> Self {
+        Self {
+            mcu: Arc::new(Mutex::new(Mcu {
+                request_parser,
+                response_serializer,
+                axes_configs: None,
+                target_position: Vector::default(),
+                position_steps: Vector::new(0, 0, 0),
+                movements: Vec::new(),
+                faults: VecDeque::new(),
+                responses: VecDeque::new(),
+                invalid_frames: 0,
+            })),
+        }
+    }
+
+    /// Queues a fault, faults are applied one per request in the injection order.
+    pub fn inject_fault(&self, fault: Fault) {
+        self.lock().faults.push_back(fault);
+    }
+
+    /// Current axes position in steps.
+    pub fn position_steps(&self) -> Vector<i64> {
+        self.lock().position_steps.clone()
+    }
+
+    /// Current axes position, i.e. the steps done times the configured step lengths.
+    pub fn position(&self) -> Vector<f32> {
+        let mcu = self.lock();
+        let mut position = Vector::default();
+        if let Some(axes_configs) = &mcu.axes_configs {
+            for axis in &AXES {
+                position.set(axis, *mcu.position_steps.get(axis) as f32 * axes_configs[axis].step_length);
+            }
+        }
+        position
+    }
+
+    pub fn movements(&self) -> Vec<SimulatedMovement> {
+        self.lock().movements.clone()
+    }
+
+    pub fn is_configured(&self) -> bool {
+        self.lock().axes_configs.is_some()
+    }
+
+    /// Number of written frames which could not be decoded as a request, those are left unanswered.
+    pub fn invalid_frames(&self) -> usize {
+        self.lock().invalid_frames
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Mcu> {
+        self.mcu.lock().expect("simulator state poisoned")
+    }
+
+    fn get_mut(&mut self) -> Result<MutexGuard<'_, Mcu>, IpcError> {
+        self.mcu.lock().map_err(|err| IpcError::Unavailable(err.to_string()))
+    }
+}
+
+struct Mcu {
+    request_parser: Box<RequestParser>,
+    response_serializer: Box<ResponseSerializer>,
+    axes_configs: Option<HashMap<Axis, AxisConfig>>,
+    // Unquantized sum of the requested movements, steps are derived from it so rounding errors do not add up
+    target_position: Vector<f32>,
+    position_steps: Vector<i64>,
+    movements: Vec<SimulatedMovement>,
+    faults: VecDeque<Fault>,
+    responses: VecDeque<Vec<u8>>,
+    invalid_frames: usize,
+}
+
+impl Mcu {
+    fn handle_frame(&mut self, frame: &Vec<u8>) -> Result<(), IpcError> {
+        let Ok(request) = self.request_parser.transform(frame) else {
+            self.invalid_frames += 1;
+            return Ok(());
+        };
+        let response = match self.faults.pop_front() {
+            Some(Fault::Failure(message)) => failure(message),
+            Some(Fault::DropResponse) => {
+                self.execute(&request.payload);
+                return Ok(());
+            },
+            Some(Fault::Garbage(garbage)) => {
+                self.execute(&request.payload);
+                self.responses.push_back(garbage);
+                return Ok(());
+            },
+            None => self.execute(&request.payload),
+        };
+        let serial_response = self.response_serializer
+            .transform(&Correlated { request_id: request.request_id, payload: response })
+            .map_err(|err| IpcError::Unavailable(format!("failed to serialize the response: {}", ErrorChain(&err))))?;
+        self.responses.push_back(serial_response);
+        Ok(())
+    }
+
+    fn execute(&mut self, request: &MovementApiRequest) -> MovementApiResponse {
+        match request {
+            MovementApiRequest::Config { axes_configs } => self.configure(axes_configs),
+            MovementApiRequest::LinearMovement { destination, speed } => self.move_linear(destination, *speed),
+            MovementApiRequest::RotationalMovement { .. } => failure("rotational movements are not supported".into()),
+        }
+    }
+
+    fn configure(&mut self, axes_configs: &HashMap<Axis, AxisConfig>) -> MovementApiResponse {
+        if let Some(axis) = AXES.iter().find(|axis| axes_configs.get(axis).is_none_or(|config| config.step_length <= 0.0)) {
+            return failure(format!("no valid config for the {axis:?} axis"));
+        }
+        self.axes_configs = Some(axes_configs.clone());
+        // Step lengths may have changed, the steps position stays where the motors are
+        self.target_position = Vector::default();
+        for axis in &AXES {
+            self.target_position.set(axis, *self.position_steps.get(axis) as f32 * axes_configs[axis].step_length);
+        }
+        success()
+    }
+
+    fn move_linear(&mut self, delta: &Vector<f32>, speed: f32) -> MovementApiResponse {
+        let Some(axes_configs) = &self.axes_configs else {
+            return failure("axes are not configured".into());
+        };
+        if speed <= 0.0 {
+            return failure(format!("invalid speed: {speed}"));
+        }
+        let mut steps = Vector::new(0, 0, 0);
+        let mut squared_distance = 0.0;
+        for axis in &AXES {
+            let step_length = axes_configs[axis].step_length;
+            let target = self.target_position.get(axis) + delta.get(axis);
+            let target_steps = (target / step_length).round() as i64;
+            let axis_steps = target_steps - self.position_steps.get(axis);
+            squared_distance += (axis_steps as f32 * step_length).powi(2);
+            self.target_position.set(axis, target);
+            self.position_steps.set(axis, target_steps);
+            steps.set(axis, axis_steps);
+        }
+        self.movements.push(SimulatedMovement {
+            steps,
+            duration: Duration::from_secs_f32(squared_distance.sqrt() / speed),
+        });
+        success()
+    }
+}
+
+fn success() -> MovementApiResponse {
+    MovementApiResponse { status: StatusCode::Success, message: None }
+}
+
+fn failure(message: String) -> MovementApiResponse {
+    MovementApiResponse { status: StatusCode::Error, message: Some(message) }
+}
+
+/// Reads the queued responses, times out right away when there is none.
+pub struct SimulatorReader {
+    simulator: McuSimulator,
+}
+
+impl SimulatorReader {
+    pub fn new(simulator: &McuSimulator) -> Self {
+        Self { simulator: simulator.clone() }
+    }
+}
+
+impl IpcReader<Vec<u8>, IpcError> for SimulatorReader {
+    fn read_data(&mut self) -> Result<Vec<u8>, IpcError> {
+        self.simulator.get_mut()?.responses.pop_front().ok_or(IpcError::Timeout)
+    }
+}
+
+pub struct SimulatorWriter {
+    simulator: McuSimulator,
+}
+
+impl SimulatorWriter {
+    pub fn new(simulator: &McuSimulator) -> Self {
+        Self { simulator: simulator.clone() }
+    }
+}
+
+impl IpcWriter<Vec<u8>, IpcError> for SimulatorWriter {
+    fn write_data(&mut self, data: &Vec<u8>) -> Result<(), IpcError> {
+        self.simulator.get_mut()?.handle_frame(data)
+    }
+}
+
+#[cfg(test)]
+mod tests;
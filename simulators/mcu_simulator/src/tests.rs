@@ -0,0 +1,152 @@
+use super::*;
+use client::{ClientError, ServiceClient};
+use gcode_processor::{GcodeProcessor, GcodeProcessorError, GcodeProcessorState, StateStorage, StateStorageError};
+use movement_data::PicoStepperConfig;
+use movement_service_client::{
+    JsonRequestParser, JsonRequestSerializer, JsonResponseParser, JsonResponseSerializer, MovementServiceClient,
+    ProtoRequestParser, ProtoRequestSerializer, ProtoResponseParser, ProtoResponseSerializer
+};
+
+#[test]
+fn sanity() {
+    // GIVEN
+    let simulator = McuSimulator::new(Box::new(ProtoRequestParser), Box::new(ProtoResponseSerializer));
+    let mut client = MovementServiceClient::new(
+        Box::new(SimulatorReader::new(&simulator)),
+        Box::new(SimulatorWriter::new(&simulator)),
+        Box::new(ProtoRequestSerializer),
+        Box::new(ProtoResponseParser),
+    );
+    let test_move = MovementApiRequest::LinearMovement {
+        destination: Vector::new(1.004, -0.5, 0.0),
+        speed: 2.0,
+    };
+
+    // WHEN
+    let unconfigured_response = client.run_request(&test_move).unwrap();
+    let config_response = client.run_request(&MovementApiRequest::Config { axes_configs: generate_axes_cfg(0.01) }).unwrap();
+    let move_response = client.run_request(&test_move).unwrap();
+    let _ = client.run_request(&test_move).unwrap();
+
+    // THEN
+    assert_eq!(StatusCode::Error, unconfigured_response.status);
+    assert_eq!(StatusCode::Success, config_response.status);
+    assert_eq!(StatusCode::Success, move_response.status);
+    let movements = simulator.movements();
+    assert_eq!(2, movements.len());
+    assert_eq!((100, -50, 0), steps_tuple(&movements[0].steps));
+    // 2.008 rounds up to 201 steps, so the second move makes up for the first one
+    assert_eq!((101, -50, 0), steps_tuple(&movements[1].steps));
+    assert_eq!((201, -100, 0), steps_tuple(&simulator.position_steps()));
+    let expected_duration = (1.0f32 + 0.25).sqrt() / 2.0;
+    assert!((movements[0].duration.as_secs_f32() - expected_duration).abs() < 1e-4);
+}
+
+#[test]
+fn gcode_end_to_end() {
+    // GIVEN
+    let simulator = McuSimulator::new(Box::new(JsonRequestParser), Box::new(JsonResponseSerializer));
+    let client = MovementServiceClient::new(
+        Box::new(SimulatorReader::new(&simulator)),
+        Box::new(SimulatorWriter::new(&simulator)),
+        Box::new(JsonRequestSerializer),
+        Box::new(JsonResponseParser),
+    );
+
+    // WHEN
+    let mut processor = GcodeProcessor::new(
+        60.0,
+        30.0,
+        Box::new(client),
+        &generate_axes_cfg(0.5),
+        Box::new(MemoryStateStorage::default()),
+    );
+
+    // THEN
+    assert!(simulator.is_configured());
+    processor.process("G00 X10 Y5").unwrap();
+    processor.process("G91").unwrap();
+    processor.process("G01 X-2.5 Z1 F10").unwrap();
+    assert_eq!((15, 10, 2), steps_tuple(&simulator.position_steps()));
+    assert_eq!(7.5, *simulator.position().get(&Axis::X));
+    assert_eq!(2, simulator.movements().len());
+}
+
+#[test]
+fn faults() {
+    // GIVEN
+    let simulator = McuSimulator::new(Box::new(ProtoRequestParser), Box::new(ProtoResponseSerializer));
+    let client = MovementServiceClient::new(
+        Box::new(SimulatorReader::new(&simulator)),
+        Box::new(SimulatorWriter::new(&simulator)),
+        Box::new(ProtoRequestSerializer),
+        Box::new(ProtoResponseParser),
+    );
+    let mut processor = GcodeProcessor::new(
+        60.0,
+        30.0,
+        Box::new(client),
+        &generate_axes_cfg(0.5),
+        Box::new(MemoryStateStorage::default()),
+    );
+
+    // WHEN
+    simulator.inject_fault(Fault::Failure("stall detected".into()));
+    simulator.inject_fault(Fault::DropResponse);
+    simulator.inject_fault(Fault::Garbage(vec![0xff, 0x00, 0x13]));
+
+    // THEN
+    assert!(matches!(processor.process("G00 X1"), Err(GcodeProcessorError::Rejected(Some(message))) if message == "stall detected"));
+    assert!(matches!(processor.process("G00 X1"), Err(GcodeProcessorError::Client(err)) if err.is_timeout()));
+    assert!(matches!(processor.process("G00 X2"), Err(GcodeProcessorError::Client(ClientError::Decode(_)))));
+    assert!(processor.process("G00 X3").is_ok());
+    assert_eq!(3, simulator.movements().len());
+
+    let mut writer = SimulatorWriter::new(&simulator);
+    writer.write_data(&b"not a request".to_vec()).unwrap();
+    assert_eq!(1, simulator.invalid_frames());
+    assert!(matches!(SimulatorReader::new(&simulator).read_data(), Err(IpcError::Timeout)));
+}
+
+fn steps_tuple(steps: &Vector<i64>) -> (i64, i64, i64) {
+    (*steps.get(&Axis::X), *steps.get(&Axis::Y), *steps.get(&Axis::Z))
+}
+
+fn generate_axes_cfg(step_length: f32) -> HashMap<Axis, AxisConfig> {
+    let directions_mapping = HashMap::from([
+        ("POSITIVE".to_string(), "CCW".to_string()),
+        ("NEGATIVE".to_string(), "CW".to_string()),
+    ]);
+    AXES.iter()
+        .zip(0u32..)
+        .map(|(axis, index)| {
+            let axis_config = AxisConfig {
+                stepper_config: PicoStepperConfig {
+                    enable_pin: 3 * index + 3,
+                    step_pin: 3 * index + 4,
+                    dir_pin: 3 * index + 5,
+                    hold_time_us: 1000,
+                },
+                step_length,
+                directions_mapping: directions_mapping.clone(),
+            };
+            (axis.clone(), axis_config)
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct MemoryStateStorage {
+    state: GcodeProcessorState,
+}
+
+impl StateStorage for MemoryStateStorage {
+    fn read_state(&self) -> Result<GcodeProcessorState, StateStorageError> {
+        Ok(self.state.clone())
+    }
+
+    fn write_state(&mut self, state: &GcodeProcessorState) -> Result<(), StateStorageError> {
+        self.state = state.clone();
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+pub use ipc::{IpcError, IpcReader, IpcWriter};
+use serialport::{SerialPort, TTYPort};
+use uart_port::UartPort;
+
+pub type RawFrameReader = dyn IpcReader<Vec<u8>, IpcError> + Send;
+pub type RawFrameWriter = dyn IpcWriter<Vec<u8>, IpcError> + Send;
+
+/// How long the fake MCU blocks on a read before checking whether it has to stop.
+const MCU_READ_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// A pty pair standing in for a serial device: the code under test opens `port_name()` like a real port,
+/// the fake MCU talks over `mcu_port()`, i.e. the master side.
+pub struct VirtualSerialPort {
+    mcu_port: UartPort,
+    port_name: String,
+    // Keeps the device side open, otherwise the master hangs up whenever the code under test closes the port
+    _device: TTYPort,
+}
+
+impl VirtualSerialPort {
+    pub fn new() -> Result<Self, IpcError> {
+        let (mut master, device) = TTYPort::pair().map_err(io::Error::from)?;
+        master.set_timeout(MCU_READ_TIMEOUT).map_err(io::Error::from)?;
+        let port_name = device
+            .name()
+            .ok_or_else(|| IpcError::Unavailable("the pty has no device path".into()))?;
+        Ok(Self {
+            mcu_port: UartPort::from_serial_port(Box::new(master)),
+            port_name,
+            _device: device,
+        })
+    }
+
+    /// Device path to open the port with, e.g. `/dev/pts/3`.
+    pub fn port_name(&self) -> &str {
+        &self.port_name
+    }
+
+    pub fn mcu_port(&self) -> &UartPort {
+        &self.mcu_port
+    }
+}
+
+/// Produces the frames the fake MCU answers a request frame with.
+pub trait McuResponder {
+    fn respond(&mut self, request: Vec<u8>) -> Result<Vec<Vec<u8>>, IpcError>;
+}
+
+impl<F> McuResponder for F
+where
+    F: FnMut(Vec<u8>) -> Result<Vec<Vec<u8>>, IpcError>,
+{
+    fn respond(&mut self, request: Vec<u8>) -> Result<Vec<Vec<u8>>, IpcError> {
+        self(request)
+    }
+}
+
+/// Hands the requests to an in-process peer, e.g. an MCU simulator, and answers with everything it has to read.
+pub struct IpcResponder {
+    reader: Box<RawFrameReader>,
+    writer: Box<RawFrameWriter>,
+}
+
+impl IpcResponder {
+    pub fn new(reader: Box<RawFrameReader>, writer: Box<RawFrameWriter>) -> Self {
+        Self { reader, writer }
+    }
+}
+
+impl McuResponder for IpcResponder {
+    fn respond(&mut self, request: Vec<u8>) -> Result<Vec<Vec<u8>>, IpcError> {
+        self.writer.write_data(&request)?;
+        let mut responses = vec![];
+        loop {
+            match self.reader.read_data() {
+                Ok(response) => responses.push(response),
+                Err(IpcError::Timeout) => return Ok(responses),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+/// Serves the requests read from the MCU side of a virtual port on a background thread until stopped.
+pub struct FakeMcu {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<Result<usize, IpcError>>>,
+}
+
+impl FakeMcu {
+    /// `reader` and `writer` frame the data over `VirtualSerialPort::mcu_port()`.
+    pub fn spawn(
+        mut reader: Box<RawFrameReader>,
+        mut writer: Box<RawFrameWriter>,
+        mut responder: Box<dyn McuResponder + Send>,
+    ) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_requested = stop.clone();
+        let handle = thread::spawn(move || {
+            let mut handled_requests = 0;
+            while !stop_requested.load(Ordering::Relaxed) {
+                let request = match reader.read_data() {
+                    Ok(request) => request,
+                    Err(IpcError::Timeout) => continue,
+                    Err(err) => return Err(err),
+                };
+                for response in responder.respond(request)? {
+                    writer.write_data(&response)?;
+                }
+                handled_requests += 1;
+            }
+            Ok(handled_requests)
+        });
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Stops serving and returns the number of handled requests, or the error the MCU stopped on.
+    pub fn stop(mut self) -> Result<usize, IpcError> {
+        self.join()
+    }
+
+    fn join(&mut self) -> Result<usize, IpcError> {
+        self.stop.store(true, Ordering::Relaxed);
+        match self.handle.take().map(JoinHandle::join) {
+            Some(Ok(result)) => result,
+            Some(Err(_)) => Err(IpcError::Unavailable("the fake MCU thread panicked".into())),
+            None => Ok(0),
+        }
+    }
+}
+
+impl Drop for FakeMcu {
+    fn drop(&mut self) {
+        let _ = self.join();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uart_sized_package_reader_writer::{DefaultSizeDecoder, DefaultSizeEncoder, UartSizedPackageReader, UartSizedPackageWriter};
+
+    use super::*;
+
+    const TEST_PREAMBLE: &[u8] = b"MSG_PREAMBLE";
+
+    #[test]
+    fn sanity() {
+        // GIVEN
+        let virtual_port = VirtualSerialPort::new().unwrap();
+        let (mcu_reader, mcu_writer) = generate_reader_writer(virtual_port.mcu_port());
+        let fake_mcu = FakeMcu::spawn(
+            mcu_reader,
+            mcu_writer,
+            Box::new(|mut request: Vec<u8>| {
+                request.reverse();
+                Ok(vec![request])
+            }),
+        );
+
+        // WHEN
+        let port = UartPort::new(virtual_port.port_name(), 115200, Duration::from_secs(1)).unwrap();
+        let (mut reader, mut writer) = generate_reader_writer(&port);
+
+        // THEN
+        writer.write_data(&b"ping".to_vec()).unwrap();
+        assert_eq!(b"gnip".to_vec(), reader.read_data().unwrap());
+        writer.write_data(&b"pong".to_vec()).unwrap();
+        assert_eq!(b"gnop".to_vec(), reader.read_data().unwrap());
+        // The device path can be opened again once closed
+        drop((port, reader, writer));
+        let port = UartPort::new(virtual_port.port_name(), 115200, Duration::from_secs(1)).unwrap();
+        let (mut reader, mut writer) = generate_reader_writer(&port);
+        writer.write_data(&b"abc".to_vec()).unwrap();
+        assert_eq!(b"cba".to_vec(), reader.read_data().unwrap());
+        assert_eq!(3, fake_mcu.stop().unwrap());
+    }
+
+    fn generate_reader_writer(port: &UartPort) -> (Box<RawFrameReader>, Box<RawFrameWriter>) {
+        (
+            Box::new(UartSizedPackageReader::new(port, TEST_PREAMBLE, Box::new(DefaultSizeDecoder::new(4).unwrap()), None, 1024)),
+            Box::new(UartSizedPackageWriter::new(port, TEST_PREAMBLE, Box::new(DefaultSizeEncoder::new(4).unwrap()), None)),
+        )
+    }
+}
@@ -1,7 +1,8 @@
 use std::{collections::HashMap, time::Duration};
 
+use ipc::ErrorChain;
 use movement_data::Vector;
-use uart_port::UartPort;
+use uart_port::{UartPort, UartPortSelector, UartPortSettings, UartTransactor};
 use movement_service_client::{
     MovementApiRequest,
     MovementServiceClient,
@@ -17,26 +18,33 @@ use uart_sized_package_reader_writer::{
 };
 
 fn main() {
-    let uart_port_name = "/dev/ttyACM0";
+    // Raspberry Pi Pico running the movement service
+    let uart_port_selector = UartPortSelector {
+        vendor_id: Some("2e8a".into()),
+        ..Default::default()
+    };
     let baud_rate = 115200;
     let response_timeout = Duration::from_secs(10);
     let preamble = b"MSG_PREAMBLE";
     let encoded_size_len = 4;
+    let max_package_size = 4096;
 
-    let uart_port = UartPort::new(uart_port_name, baud_rate, response_timeout).unwrap();
+    let uart_port = UartPort::discover(&uart_port_selector, baud_rate, &UartPortSettings::new(response_timeout)).unwrap();
     let uart_reader = UartSizedPackageReader::new(
         &uart_port,
         preamble,
-        Box::new(DefaultSizeDecoder::new(encoded_size_len)),
+        Box::new(DefaultSizeDecoder::new(encoded_size_len).unwrap()),
+        None,
+        max_package_size,
     );
     let uart_writer = UartSizedPackageWriter::new(
         &uart_port,
         preamble,
-        Box::new(DefaultSizeEncoder::new(encoded_size_len)),
+        Box::new(DefaultSizeEncoder::new(encoded_size_len).unwrap()),
+        None,
     );
-    let mut client = MovementServiceClient::new(
-        Box::new(uart_reader),
-            Box::new(uart_writer),
+    let mut client = MovementServiceClient::with_transactor(
+        Box::new(UartTransactor::new(&uart_port, Box::new(uart_reader), Box::new(uart_writer))),
         Box::new(ProtoRequestSerializer),
         Box::new(ProtoResponseParser),
     );
@@ -120,7 +128,7 @@ fn main() {
         let response = client.run_request(test_request);
         match response {
             Ok(_) => println!("request processed, response: {:?}", response),
-            Err(e) => println!("error processing request: {e}"),
+            Err(e) => println!("error processing request: {}", ErrorChain(&e)),
         }
     }
 }
\ No newline at end of file